@@ -1,12 +1,20 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use rust_decimal::Decimal;
 use std::env;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use tradedoc::templates::batch::{write_pdf_batch, BatchEntry, BatchOptions, DocumentKind};
+use tradedoc::templates::declarative::DeclarativeTemplate;
+use tradedoc::templates::diagnostics::GenerationReport;
+use tradedoc::templates::document::generate_pdf;
 use tradedoc::templates::invoice::generate_pdf_invoice;
 use tradedoc::templates::packing_list::generate_pdf_packing_list;
 use tradedoc::templates::proforma_invoice::generate_pdf_proforma_invoice;
-use tradedoc::types::{Address, Dictionary, DocumentProperties, Language, Order, OrderLineItem};
+use tradedoc::types::{
+    Address, BankAccount, Dictionary, DocumentProperties, InvoiceMeta, Language, Order,
+    OrderLineItem, OrderStatus, PaymentInfo, PricingMode, SellerInfo, SupplyDate, TaxTreatment,
+    Watermark, WatermarkContent,
+};
 
 // Embed the PNG logo in the binary
 const GOFRANZ_LOGO: &[u8] = include_bytes!("../../assets/gofranz.png");
@@ -26,21 +34,52 @@ fn create_sample_address(name: &str) -> Address {
     }
 }
 
+fn create_sample_bank_account() -> BankAccount {
+    BankAccount {
+        bank_name: "Commerzbank".to_string(),
+        iban: Some("DE89 3704 0044 0532 0130 00".to_string()),
+        bic: Some("COBADEFFXXX".to_string()),
+        account_number: None,
+        routing_number: None,
+        account_holder: None,
+    }
+}
+
 fn create_sample_data() -> (Order, Vec<OrderLineItem>, Address) {
     let order = Order {
         id: "ORD-2023-001".to_string(),
         shipping_address: create_sample_address("John Doe"),
         billing_address: create_sample_address("Jane Doe"),
+        customer_number: None,
+        customer_reference: Some("PO-4711".to_string()),
+        contact_person: None,
+        cost_center: None,
+        custom_fields: vec![],
         currency: "€".to_string(),
-        status: "Completed".to_string(),
+        status: OrderStatus::Completed,
         shipping_method: "Express".to_string(),
+        pricing_mode: PricingMode::Net,
+        tax_treatment: TaxTreatment::Standard,
         shipping_total: Decimal::new(1500, 2),
+        shipping_tax_rate: None,
+        shipping_tax_category: None,
         subtotal_before_discount: Decimal::new(50000, 2),
         discount_total: Decimal::new(5000, 2),
         subtotal: Decimal::new(45000, 2),
         tax_total: Decimal::new(9000, 2),
+        tax_lines: vec![],
         total: Decimal::new(55500, 2),
         notes: Some("Vielen Dank für Ihr Vertrauen!".to_string()),
+        payment: Some(PaymentInfo {
+            due_date: NaiveDate::from_ymd_opt(2023, 1, 16),
+            terms: Some("Payable within 14 days without deduction.".to_string()),
+            bank_accounts: vec![create_sample_bank_account()],
+        }),
+        invoice: Some(InvoiceMeta {
+            number: "2023-0042".to_string(),
+            issue_date: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            supply_date: NaiveDate::from_ymd_opt(2023, 1, 1).map(SupplyDate::Date),
+        }),
         created_at: NaiveDateTime::parse_from_str("2023-01-01 12:00:00", "%Y-%m-%d %H:%M:%S")
             .unwrap(),
         updated_at: NaiveDateTime::parse_from_str("2023-01-01 12:00:00", "%Y-%m-%d %H:%M:%S")
@@ -60,7 +99,10 @@ fn create_sample_data() -> (Order, Vec<OrderLineItem>, Address) {
             discount_total: Decimal::new(3000, 2),
             subtotal: Decimal::new(27000, 2),
             tax_total: Decimal::new(5400, 2),
+            tax_lines: vec![],
             total: Decimal::new(32400, 2),
+            tax_rate: Some(Decimal::from(20)),
+            tax_category: None,
         },
         OrderLineItem {
             id: "ITEM2".to_string(),
@@ -74,7 +116,10 @@ fn create_sample_data() -> (Order, Vec<OrderLineItem>, Address) {
             discount_total: Decimal::new(2000, 2),
             subtotal: Decimal::new(18000, 2),
             tax_total: Decimal::new(3600, 2),
+            tax_lines: vec![],
             total: Decimal::new(21600, 2),
+            tax_rate: Some(Decimal::from(20)),
+            tax_category: None,
         },
     ];
 
@@ -94,6 +139,34 @@ fn create_sample_data() -> (Order, Vec<OrderLineItem>, Address) {
     (order, order_items, warehouse_address)
 }
 
+fn create_sample_seller() -> SellerInfo {
+    SellerInfo {
+        legal_name: "Hauptlager GmbH".to_string(),
+        registration_court: Some("Amtsgericht Frankfurt am Main".to_string()),
+        registration_ids: vec![("HRB".to_string(), "123456".to_string())],
+        tax_id: Some("045 228 12345".to_string()),
+        vat_number: Some("DE987654321".to_string()),
+        directors: vec!["Erika Mustermann".to_string(), "Max Mustermann".to_string()],
+        share_capital: Some("25.000,00 €".to_string()),
+        bank_accounts: vec![create_sample_bank_account()],
+        website: Some("www.hauptlager.example".to_string()),
+        email: Some("info@hauptlager.example".to_string()),
+        phone: Some("+49 69 987 654 321".to_string()),
+    }
+}
+
+fn create_sample_custom_fields() -> Vec<(String, String)> {
+    [
+        ("Project:", "P-17"),
+        ("Contract:", "C-2023-08"),
+        ("Slot:", "08:00-12:00"),
+        ("Gate:", "3"),
+    ]
+    .into_iter()
+    .map(|(label, value)| (label.to_string(), value.to_string()))
+    .collect()
+}
+
 fn print_usage() {
     println!("Usage: cargo run --bin example [document-type] [options]");
     println!();
@@ -101,9 +174,15 @@ fn print_usage() {
     println!("  invoice          - Generate a standard invoice");
     println!("  proforma-invoice - Generate a proforma invoice (estimate)");
     println!("  packing-list     - Generate a packing list");
+    println!("  batch            - Generate all three documents into one PDF");
     println!();
     println!("Options:");
     println!("  --language <lang>    - Language (en, de, fr, es, pt, th, it) [default: en]");
+    println!("  --watermark <stamp>  - Watermark (paid, draft, copy, cancelled, void)");
+    println!("  --declarative        - Render from the built-in JSON template");
+    println!("  --gross              - Show prices including tax");
+    println!("  --seller             - Add the seller's legal details as a footer");
+    println!("  --custom-fields      - Add custom fields to the header");
     println!();
     println!("Examples:");
     println!("  cargo run --bin example invoice");
@@ -111,6 +190,12 @@ fn print_usage() {
     println!("  cargo run --bin example proforma-invoice --language fr");
 }
 
+fn print_report(report: &GenerationReport) {
+    for diagnostic in report.warnings() {
+        eprintln!("{:?}: {}", diagnostic.severity, diagnostic.message);
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
 
@@ -123,6 +208,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Parse options
     let mut language = Language::English;
+    let mut watermark = None;
+    let mut declarative = false;
+    let mut pricing_mode = PricingMode::Net;
+    let mut seller = None;
+    let mut custom_fields = vec![];
 
     let mut i = 2;
     while i < args.len() {
@@ -143,6 +233,43 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
                 i += 2;
             }
+            "--watermark" => {
+                if i + 1 >= args.len() {
+                    eprintln!("Error: --watermark requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+                let content = match args[i + 1].to_lowercase().as_str() {
+                    "paid" => WatermarkContent::Paid,
+                    "draft" => WatermarkContent::Draft,
+                    "copy" => WatermarkContent::Copy,
+                    "cancelled" => WatermarkContent::Cancelled,
+                    "void" => WatermarkContent::Void,
+                    _ => {
+                        eprintln!("Error: Unsupported watermark '{}'. Supported: paid, draft, copy, cancelled, void", &args[i + 1]);
+                        print_usage();
+                        return Ok(());
+                    }
+                };
+                watermark = Some(Watermark::new(content));
+                i += 2;
+            }
+            "--declarative" => {
+                declarative = true;
+                i += 1;
+            }
+            "--gross" => {
+                pricing_mode = PricingMode::Gross;
+                i += 1;
+            }
+            "--seller" => {
+                seller = Some(create_sample_seller());
+                i += 1;
+            }
+            "--custom-fields" => {
+                custom_fields = create_sample_custom_fields();
+                i += 1;
+            }
             _ => {
                 eprintln!("Error: Unknown option '{}'", args[i]);
                 print_usage();
@@ -151,7 +278,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    let (order, order_items, warehouse_address) = create_sample_data();
+    let (mut order, order_items, warehouse_address) = create_sample_data();
+    order.pricing_mode = pricing_mode;
+    order.custom_fields = custom_fields;
 
     let properties = DocumentProperties {
         font_normal_path: None, // Use embedded fonts
@@ -160,6 +289,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         font_size_title: Some(20.0),
         font_size_body: Some(10.0),
         font_size_label: Some(10.0),
+        watermark,
+        signature_image: None,
+        seal_image: None,
+        strict_glyphs: None,
+        validate: Some(true),
+        small_business_exemption: None,
+        seller,
     };
 
     let translation = Dictionary::for_language(language);
@@ -168,6 +304,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "invoice" => "sample_invoice",
         "proforma-invoice" => "sample_proforma_invoice",
         "packing-list" => "sample_packing_list",
+        "batch" => "sample_batch",
         _ => {
             eprintln!("Error: Unknown document type '{}'", document_type);
             print_usage();
@@ -181,37 +318,87 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         format!("{}_{}.pdf", base_filename, language.code())
     };
 
-    let pdf_data = match document_type.as_str() {
-        "invoice" => generate_pdf_invoice(
-            &order,
-            &order_items,
-            &warehouse_address,
-            properties,
-            translation,
-            Some(GOFRANZ_LOGO), // Use embedded PNG logo
-        )?,
-        "proforma-invoice" => generate_pdf_proforma_invoice(
-            &order,
-            &order_items,
+    if document_type == "batch" {
+        let entries = [
+            DocumentKind::Invoice,
+            DocumentKind::ProformaInvoice,
+            DocumentKind::PackingList,
+        ]
+        .map(|kind| BatchEntry {
+            order: &order,
+            order_items: &order_items,
+            kind,
+        });
+        // Stream straight into the file instead of buffering the whole PDF
+        let mut file = BufWriter::new(File::create(&filename)?);
+        let report = write_pdf_batch(
+            &mut file,
+            &entries,
             &warehouse_address,
             properties,
             translation,
             Some(GOFRANZ_LOGO), // Use embedded PNG logo
-        )?,
-        "packing-list" => generate_pdf_packing_list(
+            &BatchOptions {
+                duplex: true,
+                ..BatchOptions::default()
+            },
+        )?;
+
+        println!("PDF {} has been generated as '{}'", document_type, filename);
+        print_report(&report);
+        return Ok(());
+    }
+
+    let (pdf_data, report) = if declarative {
+        let template = match document_type.as_str() {
+            "invoice" => DeclarativeTemplate::invoice()?,
+            "proforma-invoice" => DeclarativeTemplate::proforma_invoice()?,
+            "packing-list" => DeclarativeTemplate::packing_list()?,
+            _ => unreachable!(),
+        };
+        generate_pdf(
+            &template,
             &order,
             &order_items,
             &warehouse_address,
             properties,
             translation,
             Some(GOFRANZ_LOGO), // Use embedded PNG logo
-        )?,
-        _ => unreachable!(),
+        )?
+    } else {
+        match document_type.as_str() {
+            "invoice" => generate_pdf_invoice(
+                &order,
+                &order_items,
+                &warehouse_address,
+                properties,
+                translation,
+                Some(GOFRANZ_LOGO), // Use embedded PNG logo
+            )?,
+            "proforma-invoice" => generate_pdf_proforma_invoice(
+                &order,
+                &order_items,
+                &warehouse_address,
+                properties,
+                translation,
+                Some(GOFRANZ_LOGO), // Use embedded PNG logo
+            )?,
+            "packing-list" => generate_pdf_packing_list(
+                &order,
+                &order_items,
+                &warehouse_address,
+                properties,
+                translation,
+                Some(GOFRANZ_LOGO), // Use embedded PNG logo
+            )?,
+            _ => unreachable!(),
+        }
     };
 
     let mut file = File::create(&filename)?;
     file.write_all(&pdf_data)?;
 
     println!("PDF {} has been generated as '{}'", document_type, filename);
+    print_report(&report);
     Ok(())
 }
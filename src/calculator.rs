@@ -0,0 +1,479 @@
+//! Derives line item and order amounts from prices, quantities, discounts and tax rates.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::collections::BTreeMap;
+
+use crate::types::{Order, OrderLineItem, TaxCategory};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    // 0.125 -> 0.13
+    #[default]
+    HalfUp,
+    // Banker's rounding: 0.125 -> 0.12, 0.135 -> 0.14
+    HalfEven,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum RoundingLevel {
+    // Tax is rounded on every line; the order tax is the sum of the line taxes
+    #[default]
+    PerLine,
+    // Tax is rounded once per tax rate, on the sum of the net amounts
+    PerDocument,
+}
+
+#[derive(Clone, Debug)]
+pub struct LineInput {
+    pub id: String,
+    pub title: String,
+    pub sku: Option<String>,
+    pub quantity: i64,
+    pub unit_price: Decimal,
+    // Discount per unit
+    pub unit_discount: Decimal,
+    // In percent, e.g. 19 for 19%
+    pub tax_rate: Decimal,
+    // None = Standard
+    pub tax_category: Option<TaxCategory>,
+}
+
+/// Order-level amounts; see [`OrderTotals::apply_to`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrderTotals {
+    pub shipping_total: Decimal,
+    pub shipping_tax_rate: Decimal,
+    pub subtotal_before_discount: Decimal,
+    pub discount_total: Decimal,
+    pub subtotal: Decimal,
+    pub tax_total: Decimal,
+    pub total: Decimal,
+}
+
+impl OrderTotals {
+    /// Copies the amounts onto `order`
+    pub fn apply_to(&self, order: &mut Order) {
+        order.shipping_total = self.shipping_total;
+        // A rate without shipping would add an empty row to the tax summary
+        order.shipping_tax_rate = if self.shipping_total.is_zero() {
+            None
+        } else {
+            Some(self.shipping_tax_rate)
+        };
+        order.subtotal_before_discount = self.subtotal_before_discount;
+        order.discount_total = self.discount_total;
+        order.subtotal = self.subtotal;
+        order.tax_total = self.tax_total;
+        order.total = self.total;
+    }
+}
+
+#[derive(Clone)]
+pub struct Calculation {
+    pub items: Vec<OrderLineItem>,
+    pub totals: OrderTotals,
+}
+
+#[derive(Clone, Debug)]
+pub struct Calculator {
+    pub rounding_mode: RoundingMode,
+    pub rounding_level: RoundingLevel,
+    // Digits after the decimal point (2 for most currencies)
+    pub decimal_places: u32,
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Self {
+            rounding_mode: RoundingMode::HalfUp,
+            rounding_level: RoundingLevel::PerLine,
+            decimal_places: 2,
+        }
+    }
+}
+
+impl Calculator {
+    pub fn round(&self, amount: Decimal) -> Decimal {
+        let strategy = match self.rounding_mode {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+        };
+        amount.round_dp_with_strategy(self.decimal_places, strategy)
+    }
+
+    /// Tax on `net` at `rate` percent, unrounded
+    fn tax(net: Decimal, rate: Decimal) -> Decimal {
+        net * rate / Decimal::ONE_HUNDRED
+    }
+
+    /// Derives all amounts of a single line; tax is always rounded per line here
+    pub fn line_item(&self, line: &LineInput) -> OrderLineItem {
+        let quantity = Decimal::from(line.quantity);
+        let subtotal_before_discount = self.round(line.unit_price * quantity);
+        let discount_total = self.round(line.unit_discount * quantity);
+        let subtotal = subtotal_before_discount - discount_total;
+        let tax_total = self.round(Self::tax(subtotal, line.tax_rate));
+
+        OrderLineItem {
+            id: line.id.clone(),
+            title: line.title.clone(),
+            sku: line.sku.clone(),
+            quantity: line.quantity,
+            unit_price: line.unit_price,
+            unit_tax: self.round(Self::tax(line.unit_price, line.tax_rate)),
+            unit_discount: line.unit_discount,
+            subtotal_before_discount,
+            discount_total,
+            subtotal,
+            tax_total,
+            tax_lines: vec![],
+            total: subtotal + tax_total,
+            tax_rate: Some(line.tax_rate),
+            tax_category: line.tax_category,
+        }
+    }
+
+    /// Moves `difference` onto the tax of the lines at `indices`, one smallest unit at a
+    /// time, starting with the lines whose per-line rounding was furthest off
+    fn spread(
+        &self,
+        items: &mut [OrderLineItem],
+        indices: &[usize],
+        rate: Decimal,
+        difference: Decimal,
+    ) {
+        if difference.is_zero() || indices.is_empty() {
+            return;
+        }
+        let unit = if difference.is_sign_negative() {
+            -Decimal::new(1, self.decimal_places)
+        } else {
+            Decimal::new(1, self.decimal_places)
+        };
+        let mut order = indices.to_vec();
+        // Largest remainder in the direction of the difference first
+        order.sort_by_key(|&i| {
+            let item = &items[i];
+            std::cmp::Reverse((Self::tax(item.subtotal, rate) - item.tax_total) * unit)
+        });
+        let steps = usize::try_from(difference / unit).unwrap_or_default();
+        for &i in order.iter().cycle().take(steps) {
+            items[i].tax_total += unit;
+            items[i].total += unit;
+        }
+    }
+
+    /// Derives line items and order totals. `shipping_total` is net, taxed at
+    /// `shipping_tax_rate` percent. With [`RoundingLevel::PerDocument`] the difference
+    /// to per-line rounding is moved onto the line taxes, so they still add up to the
+    /// order's `tax_total`.
+    pub fn calculate(
+        &self,
+        lines: &[LineInput],
+        shipping_total: Decimal,
+        shipping_tax_rate: Decimal,
+    ) -> Calculation {
+        let mut items: Vec<OrderLineItem> = lines.iter().map(|line| self.line_item(line)).collect();
+        let shipping_total = self.round(shipping_total);
+        let shipping_tax = self.round(Self::tax(shipping_total, shipping_tax_rate));
+
+        if self.rounding_level == RoundingLevel::PerDocument {
+            // Net amount per rate, rounded once per rate
+            let mut net_by_rate: BTreeMap<Decimal, Decimal> = BTreeMap::new();
+            for (line, item) in lines.iter().zip(&items) {
+                *net_by_rate.entry(line.tax_rate.normalize()).or_default() += item.subtotal;
+            }
+            for (rate, net) in net_by_rate {
+                let indices: Vec<usize> = (0..lines.len())
+                    .filter(|&i| lines[i].tax_rate.normalize() == rate)
+                    .collect();
+                let mut net = net;
+                let mut rounded: Decimal = indices.iter().map(|&i| items[i].tax_total).sum();
+                if shipping_tax_rate.normalize() == rate {
+                    net += shipping_total;
+                    rounded += shipping_tax;
+                }
+                self.spread(
+                    &mut items,
+                    &indices,
+                    rate,
+                    self.round(Self::tax(net, rate)) - rounded,
+                );
+            }
+        }
+        let tax_total = items.iter().map(|item| item.tax_total).sum::<Decimal>() + shipping_tax;
+
+        let subtotal_before_discount = items.iter().map(|item| item.subtotal_before_discount).sum();
+        let discount_total = items.iter().map(|item| item.discount_total).sum();
+        let subtotal: Decimal = items.iter().map(|item| item.subtotal).sum();
+
+        Calculation {
+            totals: OrderTotals {
+                shipping_total,
+                shipping_tax_rate,
+                subtotal_before_discount,
+                discount_total,
+                subtotal,
+                tax_total,
+                total: subtotal + shipping_total + tax_total,
+            },
+            items,
+        }
+    }
+}
+
+/// Net amount and tax of everything taxed at one rate and category
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaxSummaryRow {
+    // In percent
+    pub rate: Decimal,
+    pub category: TaxCategory,
+    pub net: Decimal,
+    pub tax: Decimal,
+}
+
+impl Order {
+    /// Groups `items` and shipping by tax rate and category, highest rate first.
+    /// Lines contribute their `tax_total`; shipping contributes `shipping_total` at its
+    /// rate, rounded half up like the default [`Calculator`], which spreads rounding
+    /// differences onto the lines. Lines and shipping without a rate are left out.
+    pub fn tax_summary(&self, items: &[OrderLineItem]) -> Vec<TaxSummaryRow> {
+        let mut rows: Vec<TaxSummaryRow> = vec![];
+        let mut add = |rate: Decimal, category: Option<TaxCategory>, net, tax| {
+            let rate = rate.normalize();
+            let category = category.unwrap_or_default();
+            match rows
+                .iter_mut()
+                .find(|row| row.rate == rate && row.category == category)
+            {
+                Some(row) => {
+                    row.net += net;
+                    row.tax += tax;
+                }
+                None => rows.push(TaxSummaryRow {
+                    rate,
+                    category,
+                    net,
+                    tax,
+                }),
+            }
+        };
+
+        for item in items {
+            if let Some(rate) = item.tax_rate {
+                add(rate, item.tax_category, item.subtotal, item.tax_total);
+            }
+        }
+        if let Some(rate) = self.shipping_tax_rate {
+            let tax = Calculator::default().round(Calculator::tax(self.shipping_total, rate));
+            add(rate, self.shipping_tax_category, self.shipping_total, tax);
+        }
+
+        rows.sort_by_key(|row| std::cmp::Reverse(row.rate));
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{line, order};
+
+    fn calculator(rounding_mode: RoundingMode, rounding_level: RoundingLevel) -> Calculator {
+        Calculator {
+            rounding_mode,
+            rounding_level,
+            decimal_places: 2,
+        }
+    }
+
+    // Ten lines of 0.07 at 19%: 0.0133 tax each, 0.133 in total
+    fn small_lines() -> Vec<LineInput> {
+        (0..10)
+            .map(|i| line(&i.to_string(), 1, Decimal::new(7, 2), Decimal::from(19)))
+            .collect()
+    }
+
+    #[test]
+    fn rounds_midpoints_by_mode() {
+        let half_up = calculator(RoundingMode::HalfUp, RoundingLevel::PerLine);
+        let half_even = calculator(RoundingMode::HalfEven, RoundingLevel::PerLine);
+        assert_eq!(half_up.round(Decimal::new(125, 3)), Decimal::new(13, 2));
+        assert_eq!(half_even.round(Decimal::new(125, 3)), Decimal::new(12, 2));
+        assert_eq!(half_up.round(Decimal::new(135, 3)), Decimal::new(14, 2));
+        assert_eq!(half_even.round(Decimal::new(135, 3)), Decimal::new(14, 2));
+        assert_eq!(half_up.round(Decimal::new(-125, 3)), Decimal::new(-13, 2));
+    }
+
+    #[test]
+    fn line_tax_is_rounded_by_mode() {
+        // 0.25 at 5% is 0.0125
+        let input = line("1", 5, Decimal::new(5, 2), Decimal::from(5));
+        let half_up = calculator(RoundingMode::HalfUp, RoundingLevel::PerLine).line_item(&input);
+        let half_even =
+            calculator(RoundingMode::HalfEven, RoundingLevel::PerLine).line_item(&input);
+        assert_eq!(half_up.tax_total, Decimal::new(1, 2));
+        assert_eq!(half_even.tax_total, Decimal::new(1, 2));
+        assert_eq!(half_up.subtotal, Decimal::new(25, 2));
+        assert_eq!(half_up.total, Decimal::new(26, 2));
+    }
+
+    #[test]
+    fn per_line_adds_up_rounded_line_taxes() {
+        let calculation = calculator(RoundingMode::HalfUp, RoundingLevel::PerLine).calculate(
+            &small_lines(),
+            Decimal::ZERO,
+            Decimal::ZERO,
+        );
+        assert_eq!(calculation.totals.tax_total, Decimal::new(10, 2));
+        assert!(calculation
+            .items
+            .iter()
+            .all(|item| item.tax_total == Decimal::new(1, 2)));
+        assert_eq!(calculation.totals.total, Decimal::new(80, 2));
+    }
+
+    #[test]
+    fn per_document_spreads_difference_onto_lines() {
+        let calculation = calculator(RoundingMode::HalfUp, RoundingLevel::PerDocument).calculate(
+            &small_lines(),
+            Decimal::ZERO,
+            Decimal::ZERO,
+        );
+        let totals = &calculation.totals;
+        assert_eq!(totals.tax_total, Decimal::new(13, 2));
+        assert_eq!(
+            calculation
+                .items
+                .iter()
+                .map(|item| item.tax_total)
+                .sum::<Decimal>(),
+            totals.tax_total
+        );
+        assert!(calculation
+            .items
+            .iter()
+            .all(|item| item.total == item.subtotal + item.tax_total));
+        assert_eq!(
+            calculation
+                .items
+                .iter()
+                .map(|item| item.total)
+                .sum::<Decimal>(),
+            totals.total
+        );
+    }
+
+    #[test]
+    fn per_document_rounds_once_per_rate_with_shipping() {
+        let mut lines = small_lines();
+        lines.push(line("reduced", 3, Decimal::new(33, 2), Decimal::from(7)));
+        // 0.99 at 7% is 0.0693; shipping 4.99 at 19% is 0.9481
+        let calculation = calculator(RoundingMode::HalfEven, RoundingLevel::PerDocument).calculate(
+            &lines,
+            Decimal::new(499, 2),
+            Decimal::from(19),
+        );
+        // 19%: (0.70 + 4.99) * 0.19 = 1.0811; 7%: 0.0693
+        assert_eq!(calculation.totals.tax_total, Decimal::new(115, 2));
+        let line_tax: Decimal = calculation.items.iter().map(|item| item.tax_total).sum();
+        // Shipping keeps its own rounded tax
+        assert_eq!(line_tax + Decimal::new(95, 2), calculation.totals.tax_total);
+        assert_eq!(calculation.items[10].tax_total, Decimal::new(7, 2));
+    }
+
+    #[test]
+    fn per_document_takes_away_from_lines() {
+        // 0.05 at 10%: 0.005 rounds up on every line, 0.015 rounds up once
+        let lines: Vec<LineInput> = (0..3)
+            .map(|i| line(&i.to_string(), 1, Decimal::new(5, 2), Decimal::from(10)))
+            .collect();
+        let calculation = calculator(RoundingMode::HalfUp, RoundingLevel::PerDocument).calculate(
+            &lines,
+            Decimal::ZERO,
+            Decimal::ZERO,
+        );
+        assert_eq!(calculation.totals.tax_total, Decimal::new(2, 2));
+        let mut taxes: Vec<Decimal> = calculation
+            .items
+            .iter()
+            .map(|item| item.tax_total)
+            .collect();
+        taxes.sort();
+        assert_eq!(
+            taxes,
+            vec![Decimal::ZERO, Decimal::new(1, 2), Decimal::new(1, 2)]
+        );
+    }
+
+    #[test]
+    fn shipping_rate_is_only_set_with_shipping() {
+        let calculator = Calculator::default();
+        let (without, _) = order(
+            &calculator,
+            &small_lines(),
+            Decimal::ZERO,
+            Decimal::from(19),
+        );
+        assert_eq!(without.shipping_tax_rate, None);
+        let (with, _) = order(
+            &calculator,
+            &small_lines(),
+            Decimal::new(499, 2),
+            Decimal::from(19),
+        );
+        assert_eq!(with.shipping_tax_rate, Some(Decimal::from(19)));
+        assert_eq!(with.tax_total, Decimal::new(105, 2));
+    }
+
+    #[test]
+    fn tax_summary_adds_up_to_tax_total() {
+        let mut lines = small_lines();
+        lines.push(line("reduced", 3, Decimal::new(33, 2), Decimal::from(7)));
+        for rounding_level in [RoundingLevel::PerLine, RoundingLevel::PerDocument] {
+            for rounding_mode in [RoundingMode::HalfUp, RoundingMode::HalfEven] {
+                let calculator = calculator(rounding_mode, rounding_level);
+                let (order, items) = order(
+                    &calculator,
+                    &lines,
+                    Decimal::new(1, 2),
+                    Decimal::new(195, 1),
+                );
+                let summary = order.tax_summary(&items);
+                assert_eq!(
+                    summary.iter().map(|row| row.tax).sum::<Decimal>(),
+                    order.tax_total
+                );
+                assert_eq!(summary[0].rate, Decimal::new(195, 1));
+                assert_eq!(summary[0].net, Decimal::new(1, 2));
+            }
+        }
+    }
+
+    #[test]
+    fn tax_summary_leaves_out_lines_without_rate() {
+        let lines = [
+            line("a", 1, Decimal::from(10), Decimal::from(19)),
+            line("b", 1, Decimal::from(20), Decimal::from(7)),
+        ];
+        let (order, mut items) = order(
+            &Calculator::default(),
+            &lines,
+            Decimal::new(495, 2),
+            Decimal::from(19),
+        );
+        // Taxed, but the rate is unknown, e.g. imported from a shop
+        items[1].tax_rate = None;
+
+        let summary = order.tax_summary(&items);
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].rate, Decimal::from(19));
+        assert_eq!(summary[0].net, Decimal::new(1495, 2));
+        // 1.90 on the line and 0.94 on shipping, none of the 1.40 on the rate-less line
+        assert_eq!(summary[0].tax, Decimal::new(284, 2));
+
+        // Whatever the rate-less line reports, its tax doesn't end up on shipping
+        items[1].tax_total = Decimal::ZERO;
+        assert_eq!(order.tax_summary(&items), summary);
+    }
+}
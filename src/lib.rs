@@ -1,2 +1,8 @@
+pub mod calculator;
+pub mod numbering;
 pub mod templates;
 pub mod types;
+pub mod validation;
+
+#[cfg(test)]
+mod test_utils;
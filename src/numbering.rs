@@ -0,0 +1,410 @@
+//! Gapless invoice numbers from patterns like `{prefix}-{yyyy}-{seq:06}`.
+//!
+//! Every distinct number "shape" has its own counter: the counter key is the number
+//! with the sequence left out, so `{yyyy}` restarts the sequence every year, `{mm}`
+//! every month and `{brand}` keeps one sequence per brand.
+
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::types::{InvoiceMeta, SupplyDate};
+
+#[derive(Debug)]
+pub enum NumberingError {
+    // Pattern has an unknown or malformed placeholder, or no `{seq}`
+    Pattern(String),
+    // Counter file could not be parsed
+    CorruptStore(String),
+    IoError(io::Error),
+}
+
+impl fmt::Display for NumberingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NumberingError::Pattern(e) => write!(f, "Invalid number pattern: {}", e),
+            NumberingError::CorruptStore(e) => write!(f, "Corrupt counter store: {}", e),
+            NumberingError::IoError(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NumberingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NumberingError::IoError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NumberingError {
+    fn from(err: io::Error) -> Self {
+        NumberingError::IoError(err)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Prefix,
+    Brand,
+    // 2023
+    Year,
+    // 23
+    ShortYear,
+    // 01 to 12
+    Month,
+    // Zero-padded to `width` digits
+    Sequence { width: usize },
+}
+
+/// A parsed number pattern. Placeholders: `{prefix}`, `{brand}`, `{yyyy}`, `{yy}`,
+/// `{mm}` and exactly one `{seq}`, optionally zero-padded, e.g. `{seq:06}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NumberPattern {
+    parts: Vec<Part>,
+}
+
+impl NumberPattern {
+    pub fn parse(pattern: &str) -> Result<Self, NumberingError> {
+        let mut parts = vec![];
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                parts.push(Part::Literal(rest[..start].to_string()));
+            }
+            let Some(end) = rest[start..].find('}') else {
+                return Err(NumberingError::Pattern(format!(
+                    "unclosed '{{' in '{}'",
+                    pattern
+                )));
+            };
+            let name = &rest[start + 1..start + end];
+            parts.push(match name {
+                "prefix" => Part::Prefix,
+                "brand" => Part::Brand,
+                "yyyy" => Part::Year,
+                "yy" => Part::ShortYear,
+                "mm" => Part::Month,
+                "seq" => Part::Sequence { width: 0 },
+                _ => match name.strip_prefix("seq:").map(str::parse) {
+                    Some(Ok(width)) => Part::Sequence { width },
+                    _ => {
+                        return Err(NumberingError::Pattern(format!(
+                            "unknown placeholder '{{{}}}' in '{}'",
+                            name, pattern
+                        )))
+                    }
+                },
+            });
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+
+        let sequences = parts
+            .iter()
+            .filter(|part| matches!(part, Part::Sequence { .. }))
+            .count();
+        if sequences != 1 {
+            return Err(NumberingError::Pattern(format!(
+                "'{}' must contain exactly one {{seq}}",
+                pattern
+            )));
+        }
+        Ok(Self { parts })
+    }
+
+    /// The number for `sequence`; without one, the sequence is written as `{seq}`,
+    /// which gives the counter key
+    fn format(&self, prefix: &str, brand: &str, date: NaiveDate, sequence: Option<u64>) -> String {
+        let mut number = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => number.push_str(text),
+                Part::Prefix => number.push_str(prefix),
+                Part::Brand => number.push_str(brand),
+                Part::Year => number.push_str(&format!("{:04}", date.year())),
+                Part::ShortYear => number.push_str(&format!("{:02}", date.year() % 100)),
+                Part::Month => number.push_str(&format!("{:02}", date.month())),
+                Part::Sequence { width } => match sequence {
+                    Some(sequence) => number.push_str(&format!("{:0width$}", sequence)),
+                    None => number.push_str("{seq}"),
+                },
+            }
+        }
+        number
+    }
+}
+
+/// Persists the counters. Implementations must make [`CounterStore::next`] atomic
+/// across all users of the store, or numbers are handed out twice.
+pub trait CounterStore {
+    /// Increments the counter `key` and returns its new value; the first is 1
+    fn next(&self, key: &str) -> Result<u64, NumberingError>;
+}
+
+/// Counters in memory, for a single process and tests
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    counters: Mutex<HashMap<String, u64>>,
+}
+
+impl CounterStore for MemoryStore {
+    fn next(&self, key: &str) -> Result<u64, NumberingError> {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let counter = counters.entry(key.to_string()).or_default();
+        *counter += 1;
+        Ok(*counter)
+    }
+}
+
+/// Counters in a JSON file, shared by any number of processes on the same machine.
+///
+/// Every increment holds an exclusive lock on `<path>.lock` while it reads the file,
+/// writes the new counters to `<path>.tmp` and renames that over `<path>`, so a crash
+/// never leaves a half-written file behind.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// The file is created on first use
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `path` with `extension` appended, e.g. "counters.json.lock"
+    fn sibling(&self, extension: &str) -> PathBuf {
+        let mut path = OsString::from(self.path.as_os_str());
+        path.push(extension);
+        path.into()
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, u64>, NumberingError> {
+        match fs::read(&self.path) {
+            Ok(data) => serde_json::from_slice(&data).map_err(|e| {
+                NumberingError::CorruptStore(format!("{}: {}", self.path.display(), e))
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, counters: &BTreeMap<String, u64>) -> Result<(), NumberingError> {
+        let tmp_path = self.sibling(".tmp");
+        let mut tmp = fs::File::create(&tmp_path)?;
+        tmp.write_all(
+            &serde_json::to_vec_pretty(counters)
+                .map_err(|e| NumberingError::CorruptStore(e.to_string()))?,
+        )?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+impl CounterStore for FileStore {
+    fn next(&self, key: &str) -> Result<u64, NumberingError> {
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.sibling(".lock"))?;
+        // Blocks until other processes are done; released when `lock` is dropped
+        lock.lock()?;
+
+        let mut counters = self.read()?;
+        let counter = counters.entry(key.to_string()).or_default();
+        *counter += 1;
+        let value = *counter;
+        self.write(&counters)?;
+        Ok(value)
+    }
+}
+
+/// Hands out invoice numbers from `store`.
+///
+/// A number is used up as soon as it is returned, so only draw one for an invoice
+/// that will be issued; otherwise the sequence has a gap.
+pub struct InvoiceNumbering<S: CounterStore> {
+    pub pattern: NumberPattern,
+    // Replaces `{prefix}`, e.g. "INV"
+    pub prefix: String,
+    pub store: S,
+}
+
+impl<S: CounterStore> InvoiceNumbering<S> {
+    /// Next number for `brand` (replaces `{brand}`), dated `date`
+    pub fn next(&self, brand: &str, date: NaiveDate) -> Result<String, NumberingError> {
+        let key = self.pattern.format(&self.prefix, brand, date, None);
+        let sequence = self.store.next(&key)?;
+        Ok(self
+            .pattern
+            .format(&self.prefix, brand, date, Some(sequence)))
+    }
+
+    /// [`InvoiceMeta`] with the next number, issued on `issue_date`
+    pub fn next_invoice(
+        &self,
+        brand: &str,
+        issue_date: NaiveDate,
+        supply_date: Option<SupplyDate>,
+    ) -> Result<InvoiceMeta, NumberingError> {
+        Ok(InvoiceMeta {
+            number: self.next(brand, issue_date)?,
+            issue_date,
+            supply_date,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn date(year: i32, month: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, 15).unwrap()
+    }
+
+    fn numbering<S: CounterStore>(pattern: &str, store: S) -> InvoiceNumbering<S> {
+        InvoiceNumbering {
+            pattern: NumberPattern::parse(pattern).unwrap(),
+            prefix: "INV".to_string(),
+            store,
+        }
+    }
+
+    /// Counter file unique to `name`; it is removed along with its lock and temporary files
+    /// when the guard is dropped
+    struct CounterFile {
+        path: PathBuf,
+    }
+
+    impl CounterFile {
+        fn remove(&self) {
+            for extension in ["", ".lock", ".tmp"] {
+                let _ = fs::remove_file(FileStore::new(&self.path).sibling(extension));
+            }
+        }
+    }
+
+    impl Drop for CounterFile {
+        fn drop(&mut self) {
+            self.remove();
+        }
+    }
+
+    fn counter_file(name: &str) -> CounterFile {
+        let file = CounterFile {
+            path: std::env::temp_dir().join(format!(
+                "tradedoc-{}-{}.json",
+                name,
+                std::process::id()
+            )),
+        };
+        file.remove();
+        file
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for pattern in [
+            "INV-{yyyy}",
+            "{seq}-{seq}",
+            "{prefix}-{seq",
+            "{prefix}-{day}-{seq}",
+            "{seq:six}",
+        ] {
+            assert!(
+                matches!(
+                    NumberPattern::parse(pattern),
+                    Err(NumberingError::Pattern(_))
+                ),
+                "{}",
+                pattern
+            );
+        }
+    }
+
+    #[test]
+    fn formats_placeholders() {
+        let pattern = NumberPattern::parse("{prefix}/{brand}/{yyyy}{yy}-{mm}/{seq:05}").unwrap();
+        assert_eq!(
+            pattern.format("INV", "ACME", date(2024, 3), Some(42)),
+            "INV/ACME/202424-03/00042"
+        );
+        assert_eq!(
+            pattern.format("INV", "ACME", date(2024, 3), None),
+            "INV/ACME/202424-03/{seq}"
+        );
+        // Padding is a minimum width
+        let pattern = NumberPattern::parse("{seq:02}").unwrap();
+        assert_eq!(pattern.format("", "", date(2024, 3), Some(123)), "123");
+    }
+
+    #[test]
+    fn restarts_per_year_and_brand() {
+        let numbering = numbering("{prefix}-{brand}-{yyyy}-{seq:04}", MemoryStore::default());
+        let next = |brand, year| numbering.next(brand, date(year, 1)).unwrap();
+        assert_eq!(next("A", 2024), "INV-A-2024-0001");
+        assert_eq!(next("A", 2024), "INV-A-2024-0002");
+        assert_eq!(next("B", 2024), "INV-B-2024-0001");
+        assert_eq!(next("A", 2025), "INV-A-2025-0001");
+        assert_eq!(next("A", 2024), "INV-A-2024-0003");
+    }
+
+    #[test]
+    fn file_store_keeps_counters_between_instances() {
+        let file = counter_file("persist");
+        let first = numbering("{prefix}-{yyyy}-{seq}", FileStore::new(&file.path));
+        assert_eq!(first.next("", date(2024, 1)).unwrap(), "INV-2024-1");
+        assert_eq!(first.next("", date(2024, 1)).unwrap(), "INV-2024-2");
+
+        let second = numbering("{prefix}-{yyyy}-{seq}", FileStore::new(&file.path));
+        let invoice = second.next_invoice("", date(2024, 2), None).unwrap();
+        assert_eq!(invoice.number, "INV-2024-3");
+        assert_eq!(invoice.issue_date, date(2024, 2));
+    }
+
+    #[test]
+    fn file_store_is_gapless_under_contention() {
+        let file = counter_file("contention");
+        let store = Arc::new(FileStore::new(&file.path));
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let store = Arc::clone(&store);
+                std::thread::spawn(move || {
+                    (0..25)
+                        .map(|_| store.next("key").unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let mut values: Vec<u64> = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect();
+        values.sort();
+        assert_eq!(values, (1..=200).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn file_store_reports_corrupt_files() {
+        let file = counter_file("corrupt");
+        fs::write(&file.path, "{ not json").unwrap();
+        assert!(matches!(
+            FileStore::new(&file.path).next("key"),
+            Err(NumberingError::CorruptStore(_))
+        ));
+    }
+}
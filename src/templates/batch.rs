@@ -0,0 +1,201 @@
+//! Many orders in one print-ready PDF.
+//!
+//! Fonts and images are embedded once for the whole batch; every document gets an outline
+//! bookmark. With the `rayon` feature, documents are laid out in parallel.
+
+use crate::types::{Address, Dictionary, DocumentProperties, Order, OrderLineItem};
+use printpdf::{ExtendedGraphicsStateMap, Mm, PdfDocument, PdfPage, PdfSaveOptions, XObjectMap};
+use std::io::Write;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use super::diagnostics::{Diagnostics, GenerationReport};
+use super::document::{render_pages, save_document, DocumentTemplate, TemplateContext};
+use super::errors::PdfError;
+use super::invoice::Invoice;
+use super::packing_list::PackingList;
+use super::pdf_utils::load_fonts;
+use super::proforma_invoice::ProformaInvoice;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocumentKind {
+    Invoice,
+    ProformaInvoice,
+    PackingList,
+}
+
+impl DocumentKind {
+    fn template(&self) -> &'static (dyn DocumentTemplate + Sync) {
+        match self {
+            DocumentKind::Invoice => &Invoice,
+            DocumentKind::ProformaInvoice => &ProformaInvoice,
+            DocumentKind::PackingList => &PackingList,
+        }
+    }
+
+    fn title<'a>(&self, translation: &'a Dictionary) -> &'a str {
+        match self {
+            DocumentKind::Invoice => &translation.invoice_title,
+            DocumentKind::ProformaInvoice => &translation.proforma_invoice_title,
+            DocumentKind::PackingList => &translation.packing_list_title,
+        }
+    }
+}
+
+/// One document of a batch.
+pub struct BatchEntry<'a> {
+    pub order: &'a Order,
+    pub order_items: &'a [OrderLineItem],
+    pub kind: DocumentKind,
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchOptions {
+    // Title stored in the PDF metadata
+    pub title: String,
+    // Pad documents with an odd page count with a blank page, so every document
+    // starts on the front side when printed double-sided
+    pub duplex: bool,
+    // Add an outline entry per document, e.g. "PACKING LIST ORD-2023-001"
+    pub bookmarks: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            title: "Batch".to_string(),
+            duplex: false,
+            bookmarks: true,
+        }
+    }
+}
+
+// Pages of one document plus the images and graphics states they reference
+type Rendered = (Vec<PdfPage>, XObjectMap, ExtendedGraphicsStateMap);
+
+fn build_batch_document(
+    entries: &[BatchEntry],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+    options: &BatchOptions,
+) -> Result<(PdfDocument, Diagnostics), PdfError> {
+    let pdf_properties = properties.input_or_default();
+    let mut doc = PdfDocument::new(&options.title);
+    let fonts = load_fonts(
+        &mut doc,
+        Some(translation.language),
+        pdf_properties.font_normal_path.as_deref(),
+        pdf_properties.font_bold_path.as_deref(),
+    )?;
+
+    // Each document is laid out into a scratch document that only collects
+    // images and graphics states; the shared fonts live in `doc`.
+    let render = |entry: &BatchEntry| -> Result<Rendered, PdfError> {
+        let mut scratch = PdfDocument::new(&options.title);
+        let ctx = TemplateContext {
+            order: entry.order,
+            order_items: entry.order_items,
+            warehouse_address,
+            properties: &pdf_properties,
+            translation: &translation,
+            fonts: &fonts,
+            logo_data,
+        };
+        let pages = render_pages(&mut scratch, entry.kind.template(), &ctx)?;
+        Ok((
+            pages,
+            scratch.resources.xobjects,
+            scratch.resources.extgstates,
+        ))
+    };
+
+    #[cfg(feature = "rayon")]
+    let rendered = entries
+        .par_iter()
+        .map(render)
+        .collect::<Result<Vec<_>, PdfError>>()?;
+    #[cfg(not(feature = "rayon"))]
+    let rendered = entries
+        .iter()
+        .map(render)
+        .collect::<Result<Vec<_>, PdfError>>()?;
+
+    let mut pages = Vec::new();
+    for (entry, (document_pages, xobjects, extgstates)) in entries.iter().zip(rendered) {
+        // Resource ids are random, so merging cannot collide
+        doc.resources.xobjects.map.extend(xobjects.map);
+        doc.resources.extgstates.map.extend(extgstates.map);
+
+        if options.bookmarks {
+            let reference = match entry.kind {
+                DocumentKind::Invoice => entry.order.invoice_number(),
+                _ => &entry.order.id,
+            };
+            // Bookmark pages are 1-based
+            doc.add_bookmark(
+                &format!("{} {}", entry.kind.title(&translation), reference),
+                pages.len() + 1,
+            );
+        }
+
+        let page_count = document_pages.len();
+        pages.extend(document_pages);
+        if options.duplex && page_count % 2 == 1 {
+            pages.push(PdfPage::new(Mm(210.0), Mm(297.0), vec![]));
+        }
+    }
+
+    doc.with_pages(pages);
+    Ok((doc, fonts.diagnostics))
+}
+
+/// Generates all `entries` into a single PDF, in order.
+pub fn generate_pdf_batch(
+    entries: &[BatchEntry],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+    options: &BatchOptions,
+) -> Result<(Vec<u8>, GenerationReport), PdfError> {
+    let (doc, diagnostics) = build_batch_document(
+        entries,
+        warehouse_address,
+        properties,
+        translation,
+        logo_data,
+        options,
+    )?;
+
+    // Generate the PDF
+    let mut warnings = Vec::new();
+    let bytes = doc.save(&PdfSaveOptions::default(), &mut warnings);
+    diagnostics.extend_printpdf(warnings);
+
+    Ok((bytes, diagnostics.into_report()))
+}
+
+/// Like [`generate_pdf_batch`], but streams the PDF into `writer`, e.g. a file.
+pub fn write_pdf_batch<W: Write>(
+    writer: &mut W,
+    entries: &[BatchEntry],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+    options: &BatchOptions,
+) -> Result<GenerationReport, PdfError> {
+    let (doc, diagnostics) = build_batch_document(
+        entries,
+        warehouse_address,
+        properties,
+        translation,
+        logo_data,
+        options,
+    )?;
+    save_document(&doc, writer, &diagnostics)?;
+    Ok(diagnostics.into_report())
+}
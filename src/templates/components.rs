@@ -0,0 +1,1057 @@
+//! Reusable layout blocks.
+//!
+//! Every component draws itself at an `(x, y)` position (in points, origin bottom left)
+//! and returns its operations together with the height it consumed below `y`,
+//! so blocks can be stacked: `y -= height`.
+
+use crate::types::{
+    Address, BankAccount, Dictionary, DocumentPropertiesDefault, Order, OrderLineItem, PaymentInfo,
+    SellerInfo, TaxCategory,
+};
+use printpdf::{
+    graphics::LinePoint, Color, Mm, Op, PaintMode, Point, Polygon, PolygonRing, Rgb, WindingOrder,
+};
+
+use super::diagnostics::{DiagnosticKind, Severity};
+use super::document::PageFlow;
+use super::errors::PdfError;
+use super::pdf_utils::{
+    draw_address, draw_bold_text, draw_horizontal_line, draw_table_header_background, draw_text,
+    format_date, format_decimal, truncate_string, FontBundle,
+};
+
+/// `text` truncated to `max_chars` characters; records a `TextTruncated` warning
+/// naming `place` if it had to be cut
+fn fit_text(text: &str, max_chars: usize, place: &str, fonts: &FontBundle) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    fonts.diagnostics.push(
+        DiagnosticKind::TextTruncated,
+        Severity::Warning,
+        format!(
+            "'{}' truncated to {} characters in {}",
+            text, max_chars, place
+        ),
+    );
+    truncate_string(text, max_chars)
+}
+
+/// x position for `text` to end at `right`, at 6 points per character
+fn right_aligned(right: i32, text: &str) -> i32 {
+    right - text.chars().count() as i32 * 6
+}
+
+pub trait Component {
+    /// Draws the component at `(x, y)`; returns the operations and the consumed height
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32);
+}
+
+fn draw_rect(x: i32, y: i32, width: i32, height: i32, mode: PaintMode) -> Op {
+    Op::DrawPolygon {
+        polygon: Polygon {
+            rings: vec![PolygonRing {
+                points: vec![
+                    LinePoint {
+                        p: Point::new(Mm(x as f32 * 0.352778), Mm(y as f32 * 0.352778)),
+                        bezier: false,
+                    },
+                    LinePoint {
+                        p: Point::new(Mm((x + width) as f32 * 0.352778), Mm(y as f32 * 0.352778)),
+                        bezier: false,
+                    },
+                    LinePoint {
+                        p: Point::new(
+                            Mm((x + width) as f32 * 0.352778),
+                            Mm((y + height) as f32 * 0.352778),
+                        ),
+                        bezier: false,
+                    },
+                    LinePoint {
+                        p: Point::new(Mm(x as f32 * 0.352778), Mm((y + height) as f32 * 0.352778)),
+                        bezier: false,
+                    },
+                ],
+            }],
+            mode,
+            winding_order: WindingOrder::NonZero,
+        },
+    }
+}
+
+fn rgb(r: f32, g: f32, b: f32) -> Color {
+    Color::Rgb(Rgb {
+        r,
+        g,
+        b,
+        icc_profile: None,
+    })
+}
+
+/// Titled postal address with optional phone and VAT lines.
+pub struct AddressBlock<'a> {
+    pub title: &'a str,
+    pub address: &'a Address,
+    // Provides the phone and VAT labels
+    pub translation: &'a Dictionary,
+}
+
+impl Component for AddressBlock<'_> {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let end_y = draw_address(
+            pdf_properties,
+            self.translation,
+            &mut ops,
+            x,
+            y,
+            self.title,
+            self.address,
+            fonts,
+        );
+        (ops, y - end_y)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+pub struct TableColumn {
+    pub header: String,
+    // Offset from the table's x position
+    pub x: i32,
+    pub width: i32,
+    pub align: Align,
+    // Longer cell text is truncated with "..."
+    pub max_chars: Option<usize>,
+}
+
+impl TableColumn {
+    pub fn new(header: &str, x: i32, width: i32, align: Align) -> Self {
+        Self {
+            header: header.to_string(),
+            x,
+            width,
+            align,
+            max_chars: None,
+        }
+    }
+
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+}
+
+pub enum TableCell {
+    Text(String),
+    // Empty box to tick by hand
+    Checkbox,
+}
+
+impl From<String> for TableCell {
+    fn from(text: String) -> Self {
+        TableCell::Text(text)
+    }
+}
+
+impl From<&str> for TableCell {
+    fn from(text: &str) -> Self {
+        TableCell::Text(text.to_string())
+    }
+}
+
+/// Table with a shaded header row; `y` is the baseline of the header.
+pub struct ItemsTable {
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<Vec<TableCell>>,
+}
+
+impl ItemsTable {
+    /// The header row and `rows` at `(x, y)`
+    fn draw_rows(
+        &self,
+        x: i32,
+        y: i32,
+        rows: &[Vec<TableCell>],
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let mut current_y = y;
+
+        // Draw table header background
+        ops.extend(draw_table_header_background(pdf_properties, current_y));
+
+        for column in &self.columns {
+            ops.extend(draw_bold_text(
+                x + column.x,
+                current_y + 5,
+                &column.header,
+                pdf_properties.font_size_label,
+                fonts,
+            ));
+        }
+
+        current_y -= 25;
+
+        for row in rows {
+            for (column, cell) in self.columns.iter().zip(row) {
+                let cell_x = x + column.x;
+                match cell {
+                    TableCell::Text(text) => {
+                        let text = match column.max_chars {
+                            Some(max_chars) => fit_text(
+                                text,
+                                max_chars,
+                                &format!("column '{}'", column.header),
+                                fonts,
+                            ),
+                            None => text.clone(),
+                        };
+                        let text_x = match column.align {
+                            Align::Left => cell_x,
+                            Align::Right => right_aligned(cell_x + column.width, &text),
+                        };
+                        ops.extend(draw_text(
+                            text_x,
+                            current_y,
+                            &text,
+                            pdf_properties.font_size_body,
+                            fonts,
+                        ));
+                    }
+                    TableCell::Checkbox => {
+                        ops.push(Op::SetOutlineColor {
+                            col: rgb(0.0, 0.0, 0.0),
+                        });
+                        ops.push(draw_rect(cell_x, current_y, 10, 10, PaintMode::Stroke));
+                    }
+                }
+            }
+
+            current_y -= 20;
+        }
+
+        (ops, y - current_y)
+    }
+
+    /// Draws the table `gap` points below the current position of `flow`. Rows that
+    /// don't fit continue on the next page, below the header row drawn again.
+    pub fn draw_flow(
+        &self,
+        x: i32,
+        gap: i32,
+        flow: &mut PageFlow,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> Result<(), PdfError> {
+        let mut rows = &self.rows[..];
+        let mut gap = gap;
+        loop {
+            // The header takes 25 points, every row 20
+            let top = if flow.at_top() { flow.y } else { flow.y - gap };
+            let fit = ((top - 25 - flow.bottom()) / 20).max(0) as usize;
+            if fit == 0 && !rows.is_empty() && !flow.at_top() {
+                flow.new_page();
+                continue;
+            }
+            let (page_rows, rest) = rows.split_at(fit.max(1).min(rows.len()));
+            flow.place("items", gap, |y| {
+                Ok(self.draw_rows(x, y, page_rows, pdf_properties, fonts))
+            })?;
+            if rest.is_empty() {
+                return Ok(());
+            }
+            rows = rest;
+            gap = 0;
+            flow.new_page();
+        }
+    }
+}
+
+impl Component for ItemsTable {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        self.draw_rows(x, y, &self.rows, pdf_properties, fonts)
+    }
+}
+
+/// Right-hand summary rows on a light background, e.g. subtotal, tax and total.
+pub struct TotalsTable {
+    // Label and formatted amount
+    pub rows: Vec<(String, String)>,
+    pub width: i32,
+}
+
+impl Component for TotalsTable {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let mut current_y = y;
+
+        for (label, amount) in &self.rows {
+            current_y -= 20;
+            ops.push(Op::SetFillColor {
+                col: rgb(0.95, 0.95, 0.95),
+            });
+            ops.push(draw_rect(x, current_y, self.width, 15, PaintMode::Fill));
+
+            // Reset text color to black for subsequent text
+            ops.push(Op::SetFillColor {
+                col: rgb(0.0, 0.0, 0.0),
+            });
+            ops.extend(draw_bold_text(
+                x + 5,
+                current_y + 2,
+                label,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+            ops.extend(draw_text(
+                right_aligned(x + self.width - 30, amount),
+                current_y + 2,
+                amount,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+        }
+
+        (ops, y - current_y)
+    }
+}
+
+/// Tax broken down by rate: net amount and tax per row, on a light background.
+pub struct TaxSummaryTable {
+    // Rate, net amount and tax column headers
+    pub headers: (String, String, String),
+    // Rate label, formatted net amount and formatted tax
+    pub rows: Vec<(String, String, String)>,
+    pub width: i32,
+}
+
+impl TaxSummaryTable {
+    /// Rows from [`Order::tax_summary`]; rates outside the standard category are labeled,
+    /// e.g. "0% Exempt"
+    pub fn for_order(
+        order: &Order,
+        items: &[OrderLineItem],
+        translation: &Dictionary,
+        width: i32,
+    ) -> Self {
+        let rows = order
+            .tax_summary(items)
+            .into_iter()
+            .map(|row| {
+                let category = match row.category {
+                    TaxCategory::Standard => None,
+                    TaxCategory::ZeroRated => Some(&translation.tax_category_zero_rated),
+                    TaxCategory::Exempt => Some(&translation.tax_category_exempt),
+                    TaxCategory::ReverseCharge => Some(&translation.tax_category_reverse_charge),
+                    TaxCategory::OutOfScope => Some(&translation.tax_category_out_of_scope),
+                };
+                let rate = match category {
+                    Some(category) => format!("{}% {}", row.rate, category),
+                    None => format!("{}%", row.rate),
+                };
+                (
+                    rate,
+                    format_decimal(row.net, &order.currency),
+                    format_decimal(row.tax, &order.currency),
+                )
+            })
+            .collect();
+
+        Self {
+            headers: (
+                translation.tax_rate_header.clone(),
+                translation.net_amount_header.clone(),
+                translation.tax_header.clone(),
+            ),
+            rows,
+            width,
+        }
+    }
+}
+
+impl Component for TaxSummaryTable {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        if self.rows.is_empty() {
+            return (ops, 0);
+        }
+        let mut current_y = y - 20;
+
+        // Right edges of the amount columns
+        let net_x = x + self.width - 95;
+        let tax_x = x + self.width - 10;
+
+        let (rate_header, net_header, tax_header) = &self.headers;
+        ops.extend(draw_bold_text(
+            x + 5,
+            current_y + 2,
+            rate_header,
+            pdf_properties.font_size_label,
+            fonts,
+        ));
+        for (header, right) in [(net_header, net_x), (tax_header, tax_x)] {
+            ops.extend(draw_bold_text(
+                right_aligned(right, header),
+                current_y + 2,
+                header,
+                pdf_properties.font_size_label,
+                fonts,
+            ));
+        }
+
+        for (rate, net, tax) in &self.rows {
+            current_y -= 20;
+            ops.push(Op::SetFillColor {
+                col: rgb(0.95, 0.95, 0.95),
+            });
+            ops.push(draw_rect(x, current_y, self.width, 15, PaintMode::Fill));
+            ops.push(Op::SetFillColor {
+                col: rgb(0.0, 0.0, 0.0),
+            });
+            ops.extend(draw_text(
+                x + 5,
+                current_y + 2,
+                rate,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+            for (amount, right) in [(net, net_x), (tax, tax_x)] {
+                ops.extend(draw_text(
+                    right_aligned(right, amount),
+                    current_y + 2,
+                    amount,
+                    pdf_properties.font_size_body,
+                    fonts,
+                ));
+            }
+        }
+
+        (ops, y - current_y)
+    }
+}
+
+/// Label/value pairs, one per line.
+pub struct KeyValueBlock {
+    pub rows: Vec<(String, String)>,
+    // Distance between label and value
+    pub label_width: i32,
+    pub line_height: i32,
+}
+
+impl Component for KeyValueBlock {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let mut current_y = y;
+
+        for (label, value) in &self.rows {
+            ops.extend(draw_bold_text(
+                x,
+                current_y,
+                label,
+                pdf_properties.font_size_label,
+                fonts,
+            ));
+            ops.extend(draw_text(
+                x + self.label_width,
+                current_y,
+                value,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+            current_y -= self.line_height;
+        }
+
+        (ops, y - current_y)
+    }
+}
+
+/// Plain text lines, `line_height` apart, e.g. references in the document header.
+pub struct TextLines {
+    pub lines: Vec<String>,
+    pub line_height: i32,
+}
+
+impl Component for TextLines {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let mut current_y = y;
+
+        for line in &self.lines {
+            ops.extend(draw_text(
+                x,
+                current_y,
+                line,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+            current_y -= self.line_height;
+        }
+
+        (ops, y - current_y)
+    }
+}
+
+/// Text lines, `line_height` apart, that continue in a second column `column_width`
+/// to the right once there are more than `max_rows`. In two columns, lines are
+/// truncated to the column width. Never takes more than `max_rows` rows: lines that
+/// don't fit into both columns are left out with a warning.
+pub struct ColumnLines {
+    pub lines: Vec<String>,
+    pub line_height: i32,
+    pub max_rows: usize,
+    pub column_width: i32,
+}
+
+impl Component for ColumnLines {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let max_rows = self.max_rows.max(1);
+        let two_columns = self.lines.len() > max_rows;
+        let rows = if two_columns {
+            self.lines.len().div_ceil(2).min(max_rows)
+        } else {
+            self.lines.len()
+        };
+        // Roughly half the font size per character, but room for at least "x..."
+        let max_chars =
+            ((self.column_width as f32 / (pdf_properties.font_size_body * 0.5)) as usize).max(4);
+
+        let shown = self.lines.len().min(2 * max_rows);
+        if shown < self.lines.len() {
+            fonts.diagnostics.push(
+                DiagnosticKind::TextTruncated,
+                Severity::Warning,
+                format!(
+                    "{} of {} lines left out, at most {} fit",
+                    self.lines.len() - shown,
+                    self.lines.len(),
+                    shown
+                ),
+            );
+        }
+
+        for (i, line) in self.lines.iter().take(shown).enumerate() {
+            let column_x = x + (i / rows) as i32 * self.column_width;
+            let line_y = y - (i % rows) as i32 * self.line_height;
+            let line = if two_columns {
+                fit_text(line, max_chars, "a two-column list", fonts)
+            } else {
+                line.clone()
+            };
+            ops.extend(draw_text(
+                column_x,
+                line_y,
+                &line,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+        }
+
+        (ops, rows as i32 * self.line_height)
+    }
+}
+
+/// Titled free text, wrapped at `max_chars` per line.
+pub struct NotesBlock {
+    pub title: String,
+    pub text: String,
+    pub max_chars: usize,
+}
+
+/// Splits `text` into lines of at most `max_chars`, breaking between words
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for mut word in paragraph.split_whitespace() {
+            // Words longer than a line, e.g. an IBAN without spaces, are broken anywhere
+            while word.chars().count() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let (split, _) = word.char_indices().nth(max_chars).unwrap_or_default();
+                lines.push(word[..split].to_string());
+                word = &word[split..];
+            }
+            if !line.is_empty() && line.chars().count() + word.chars().count() + 1 > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+impl Component for NotesBlock {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let mut current_y = y;
+
+        ops.extend(draw_text(
+            x,
+            current_y,
+            &self.title,
+            pdf_properties.font_size_body,
+            fonts,
+        ));
+        for line in wrap(&self.text, self.max_chars) {
+            current_y -= 15;
+            ops.extend(draw_text(
+                x,
+                current_y,
+                &line,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+        }
+
+        (ops, y - current_y)
+    }
+}
+
+/// Label/value rows for a bank account; only the numbers that are set
+fn bank_account_rows(account: &BankAccount, translation: &Dictionary) -> Vec<(String, String)> {
+    let optional = [
+        (&translation.account_holder_label, &account.account_holder),
+        (&translation.iban_label, &account.iban),
+        (&translation.bic_label, &account.bic),
+        (&translation.account_number_label, &account.account_number),
+        (&translation.routing_number_label, &account.routing_number),
+    ];
+    let mut rows = vec![(translation.bank_label.clone(), account.bank_name.clone())];
+    rows.extend(
+        optional
+            .into_iter()
+            .filter_map(|(label, value)| Some((label.clone(), value.clone()?))),
+    );
+    rows
+}
+
+/// Titled payment information: terms, due date and one line per bank account,
+/// each wrapped at `max_chars`.
+pub struct PaymentBlock {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub max_chars: usize,
+}
+
+impl PaymentBlock {
+    /// Lines like "Due Date: 2023-01-15" and
+    /// "Bank: Commerzbank, IBAN: DE89 ..., BIC: COBADEFFXXX"
+    pub fn for_payment(payment: &PaymentInfo, translation: &Dictionary) -> Self {
+        let join = |rows: Vec<(String, String)>| {
+            rows.into_iter()
+                .map(|(label, value)| format!("{} {}", label, value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut lines: Vec<String> = payment.terms.iter().cloned().collect();
+        if let Some(due_date) = payment.due_date {
+            lines.push(format!(
+                "{} {}",
+                translation.due_date_label,
+                format_date(due_date)
+            ));
+        }
+        for account in &payment.bank_accounts {
+            lines.push(join(bank_account_rows(account, translation)));
+        }
+
+        Self {
+            title: translation.payment_info_label.clone(),
+            lines,
+            max_chars: 90,
+        }
+    }
+}
+
+impl Component for PaymentBlock {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![];
+        let mut current_y = y;
+
+        ops.extend(draw_bold_text(
+            x,
+            current_y,
+            &self.title,
+            pdf_properties.font_size_label,
+            fonts,
+        ));
+        for line in self
+            .lines
+            .iter()
+            .flat_map(|line| wrap(line, self.max_chars))
+        {
+            current_y -= 15;
+            ops.extend(draw_text(
+                x,
+                current_y,
+                &line,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+        }
+
+        (ops, y - current_y)
+    }
+}
+
+/// Seller's legal details in small print below a separator line: company and register,
+/// directors, tax and contact, bank accounts. Empty columns are left out; entries too
+/// long for their column are wrapped.
+pub struct SellerFooter {
+    // Entries per column; the first entry of the first column is drawn bold
+    pub columns: Vec<Vec<String>>,
+    pub column_width: i32,
+    // At the default 7 point print, grows with larger text
+    pub line_height: i32,
+}
+
+impl SellerFooter {
+    pub fn for_seller(seller: &SellerInfo, translation: &Dictionary) -> Self {
+        let labeled = |label: &str, value: &Option<String>| {
+            value.as_ref().map(|value| format!("{} {}", label, value))
+        };
+
+        let mut company = vec![seller.legal_name.clone()];
+        company.extend(seller.registration_court.clone());
+        company.extend(
+            seller
+                .registration_ids
+                .iter()
+                .map(|(label, number)| format!("{} {}", label, number)),
+        );
+        company.extend(labeled(
+            &translation.share_capital_label,
+            &seller.share_capital,
+        ));
+
+        let mut directors = vec![];
+        if !seller.directors.is_empty() {
+            directors.push(translation.directors_label.clone());
+            directors.extend(seller.directors.iter().cloned());
+        }
+
+        let mut contact = vec![];
+        contact.extend(labeled(&translation.vat_label, &seller.vat_number));
+        contact.extend(labeled(&translation.tax_id_label, &seller.tax_id));
+        contact.extend(labeled(&translation.phone_label, &seller.phone));
+        contact.extend(seller.email.clone());
+        contact.extend(seller.website.clone());
+
+        let banks: Vec<String> = seller
+            .bank_accounts
+            .iter()
+            .flat_map(|account| bank_account_rows(account, translation))
+            .map(|(label, value)| format!("{} {}", label, value))
+            .collect();
+
+        Self {
+            columns: [company, directors, contact, banks]
+                .into_iter()
+                .filter(|column| !column.is_empty())
+                .collect(),
+            column_width: 124,
+            line_height: 9,
+        }
+    }
+
+    /// Small print: 70% of the body text
+    fn font_size(pdf_properties: &DocumentPropertiesDefault) -> f32 {
+        pdf_properties.font_size_body * 0.7
+    }
+
+    fn line_height(&self, font_size: f32) -> i32 {
+        self.line_height.max((font_size * 1.3).round() as i32)
+    }
+
+    /// Lines per column, each entry wrapped to the column width at `font_size`;
+    /// with the bold first entry
+    fn lines(&self, font_size: f32) -> Vec<Vec<(String, bool)>> {
+        // Roughly half the font size per character, 3.5 points at 7 points
+        let max_chars = (self.column_width as f32 / (font_size * 0.5)) as usize;
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                column
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(j, entry)| {
+                        wrap(entry, max_chars)
+                            .into_iter()
+                            .map(move |line| (line, i == 0 && j == 0))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Height from the separator line down to the baseline of the last line
+    pub fn height(&self, pdf_properties: &DocumentPropertiesDefault) -> i32 {
+        let font_size = Self::font_size(pdf_properties);
+        let lines = self
+            .lines(font_size)
+            .iter()
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0) as i32;
+        10 + (lines - 1).max(0) * self.line_height(font_size)
+    }
+}
+
+impl Component for SellerFooter {
+    fn draw(
+        &self,
+        x: i32,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        fonts: &FontBundle,
+    ) -> (Vec<Op>, i32) {
+        let mut ops = vec![draw_horizontal_line(y)];
+        let font_size = Self::font_size(pdf_properties);
+        let line_height = self.line_height(font_size);
+
+        for (i, column) in self.lines(font_size).into_iter().enumerate() {
+            let column_x = x + i as i32 * self.column_width;
+            let mut current_y = y - 10;
+            for (line, bold) in column {
+                if bold {
+                    ops.extend(draw_bold_text(column_x, current_y, &line, font_size, fonts));
+                } else {
+                    ops.extend(draw_text(column_x, current_y, &line, font_size, fonts));
+                }
+                current_y -= line_height;
+            }
+        }
+
+        (ops, self.height(pdf_properties))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::pdf_utils::load_fonts;
+    use crate::test_utils::properties;
+    use printpdf::{PdfDocument, TextItem};
+
+    fn fonts() -> FontBundle {
+        load_fonts(&mut PdfDocument::new("Test"), None, None, None).unwrap()
+    }
+
+    /// x position (in points) `text` is written at
+    fn text_x(ops: &[Op], text: &str) -> f32 {
+        let mut x = None;
+        for op in ops {
+            match op {
+                Op::SetTextCursor { pos } => x = Some(pos.x.0),
+                Op::WriteText { items, .. }
+                    if items
+                        .iter()
+                        .any(|item| matches!(item, TextItem::Text(t) if t == text)) =>
+                {
+                    return x.unwrap();
+                }
+                _ => {}
+            }
+        }
+        panic!("'{}' not drawn", text)
+    }
+
+    #[test]
+    fn right_aligns_by_characters() {
+        let fonts = fonts();
+        let properties = properties().input_or_default();
+        // Same number of characters, more bytes
+        let texts = ["EUR 1.234,50", "€ 101.234,50", "฿ 101.234,50"];
+
+        let table = ItemsTable {
+            columns: vec![TableColumn::new("Summe", 0, 100, Align::Right)],
+            rows: texts
+                .iter()
+                .map(|text| vec![TableCell::Text(text.to_string())])
+                .collect(),
+        };
+        let (ops, _) = table.draw(50, 700, &properties, &fonts);
+        let xs: Vec<f32> = texts.iter().map(|text| text_x(&ops, text)).collect();
+        assert!((xs[0] - (150.0 - 72.0)).abs() < 0.01, "{:?}", xs);
+        assert!(xs.iter().all(|x| (x - xs[0]).abs() < 0.01), "{:?}", xs);
+
+        let totals = TotalsTable {
+            rows: texts
+                .iter()
+                .map(|text| ("Gesamtsumme".to_string(), text.to_string()))
+                .collect(),
+            width: 200,
+        };
+        let (ops, _) = totals.draw(300, 500, &properties, &fonts);
+        let xs: Vec<f32> = texts.iter().map(|text| text_x(&ops, text)).collect();
+        assert!((xs[0] - (470.0 - 72.0)).abs() < 0.01, "{:?}", xs);
+        assert!(xs.iter().all(|x| (x - xs[0]).abs() < 0.01), "{:?}", xs);
+    }
+
+    #[test]
+    fn right_aligns_tax_summary_by_characters() {
+        let fonts = fonts();
+        let properties = properties().input_or_default();
+        // Thai and German headers ending at the same edge as ASCII ones
+        let table = TaxSummaryTable {
+            headers: (
+                "อัตรา".to_string(),
+                "ยอดสุทธิ".to_string(),
+                "MwSt.-Betrag".to_string(),
+            ),
+            rows: vec![(
+                "19%".to_string(),
+                "€ 100,00".to_string(),
+                "€ 19,00".to_string(),
+            )],
+            width: 200,
+        };
+        let (ops, _) = table.draw(300, 500, &properties, &fonts);
+        let net_right = 300.0 + 200.0 - 95.0;
+        let tax_right = 300.0 + 200.0 - 10.0;
+        for (text, right) in [
+            ("ยอดสุทธิ", net_right),
+            ("€ 100,00", net_right),
+            ("MwSt.-Betrag", tax_right),
+            ("€ 19,00", tax_right),
+        ] {
+            let expected = right - text.chars().count() as f32 * 6.0;
+            assert!((text_x(&ops, text) - expected).abs() < 0.01, "{}", text);
+        }
+    }
+
+    #[test]
+    fn wrap_breaks_words_longer_than_a_line() {
+        assert_eq!(
+            wrap("IBAN DE89370400440532013000 BIC", 10),
+            ["IBAN", "DE89370400", "4405320130", "00 BIC"]
+        );
+        assert_eq!(wrap("Straße", 4), ["Stra", "ße"]);
+        assert_eq!(wrap("abc", 0), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn seller_footer_wraps_to_its_columns() {
+        let fonts = fonts();
+        let seller = SellerInfo {
+            legal_name: "Müller & Söhne Büroeinrichtungen GmbH & Co. KG".to_string(),
+            registration_court: Some("Amtsgericht Frankfurt am Main".to_string()),
+            registration_ids: vec![("HRB".to_string(), "12345".to_string())],
+            tax_id: None,
+            vat_number: None,
+            directors: vec![],
+            share_capital: None,
+            bank_accounts: vec![BankAccount {
+                bank_name: "Commerzbank".to_string(),
+                iban: Some("DE89370400440532013000".to_string()),
+                ..BankAccount::default()
+            }],
+            website: None,
+            email: None,
+            phone: None,
+        };
+        let footer = SellerFooter::for_seller(&seller, &Dictionary::default());
+        let mut heights = vec![];
+        for font_size in [10.0, 20.0, 40.0] {
+            let properties = DocumentPropertiesDefault {
+                font_size_body: font_size,
+                ..properties().input_or_default()
+            };
+            let small_print = SellerFooter::font_size(&properties);
+            let lines = footer.lines(small_print);
+            for line in lines.iter().flatten() {
+                // Half the font size per character
+                let width = line.0.chars().count() as f32 * small_print * 0.5;
+                assert!(width <= footer.column_width as f32, "{:?}", line);
+            }
+            // Nothing is lost
+            let text: String = lines.iter().flatten().map(|line| line.0.as_str()).collect();
+            assert!(text.contains("DE89370400440532013000"), "{}", text);
+
+            let (ops, height) = footer.draw(50, 100, &properties, &fonts);
+            assert_eq!(height, footer.height(&properties));
+            assert!(!ops.is_empty());
+            heights.push(height);
+        }
+        assert!(
+            heights.windows(2).all(|pair| pair[0] < pair[1]),
+            "{:?}",
+            heights
+        );
+    }
+}
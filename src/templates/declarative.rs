@@ -0,0 +1,986 @@
+//! Document layouts described in JSON and interpreted at runtime.
+//!
+//! A template lists blocks per section (header, body, footer). Text supports
+//! placeholders: `{label.<dictionary field>}`, `{order.<field>}` and, inside
+//! item table cells, `{item.<field>}`. Blocks with a `y` are placed absolutely;
+//! otherwise they flow below the previous block, `gap` points further down. Body
+//! blocks that don't fit move to a new page; the items table continues there.
+
+use crate::types::{Address, Dictionary, Order, OrderLineItem, PricingMode};
+use printpdf::{Op, PdfDocument};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use super::components::{
+    AddressBlock, Align, ColumnLines, Component, ItemsTable, KeyValueBlock, NotesBlock,
+    PaymentBlock, TableCell, TableColumn, TaxSummaryTable, TextLines, TotalsTable,
+};
+use super::document::{
+    content_bottom, ensure_fits, DocumentTemplate, PageFlow, TemplateContext, HEADER_BOTTOM,
+};
+use super::errors::PdfError;
+use super::pdf_utils::{
+    custom_field_lines, draw_addresses, draw_bold_text, draw_horizontal_line, draw_line, draw_logo,
+    draw_text, format_date, format_decimal, header_fields, included_tax_rows, order_status_text,
+    supply_date_text, tax_rows, tax_treatment_notice,
+};
+
+const ORDER_FIELDS: &[&str] = &[
+    "id",
+    "currency",
+    "status",
+    "shipping_method",
+    "shipping_total",
+    "shipping_tax_rate",
+    "subtotal_before_discount",
+    "discount_total",
+    "subtotal",
+    "tax_total",
+    "total",
+    "notes",
+    "tax_treatment_notice",
+    "invoice_number",
+    "invoice_date",
+    "supply_date",
+    "customer_number",
+    "customer_reference",
+    "contact_person",
+    "cost_center",
+    "created_at",
+    "updated_at",
+    "total_items",
+];
+
+const ITEM_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "sku",
+    "title_sku",
+    "quantity",
+    "unit_price",
+    "unit_tax",
+    "unit_discount",
+    "subtotal_before_discount",
+    "discount_total",
+    "subtotal",
+    "tax_total",
+    "total",
+    "tax_rate",
+];
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FontSize {
+    Title,
+    #[default]
+    Body,
+    Label,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSource {
+    Logo,
+    Signature,
+    Seal,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressSource {
+    Warehouse,
+    Shipping,
+    Billing,
+}
+
+/// Renders a block or column only if the document has the given content
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Notes,
+    Payment,
+    // Invoice with a delivery date or service period
+    SupplyDate,
+    // Tax treatment other than standard VAT, or the small-business exemption
+    TaxTreatmentNotice,
+    // Tax is shown, i.e. no small-business exemption
+    Tax,
+    Signature,
+    Seal,
+    SignatureOrSeal,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ColumnDefinition {
+    pub header: String,
+    pub x: i32,
+    pub width: i32,
+    #[serde(default)]
+    pub align: ColumnAlign,
+    pub max_chars: Option<usize>,
+    // Cell text; ignored for checkbox columns
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub checkbox: bool,
+    pub when: Option<Condition>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnAlign {
+    #[default]
+    Left,
+    Right,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockKind {
+    Text {
+        text: String,
+        #[serde(default)]
+        bold: bool,
+        #[serde(default)]
+        size: FontSize,
+    },
+    Image {
+        source: ImageSource,
+        width_mm: f32,
+        height_mm: f32,
+    },
+    Address {
+        title: String,
+        source: AddressSource,
+    },
+    Line {
+        // Length from `x` (None = across the page)
+        width: Option<i32>,
+    },
+    ItemsTable {
+        columns: Vec<ColumnDefinition>,
+    },
+    Totals {
+        width: i32,
+        rows: Vec<(String, String)>,
+    },
+    // Net amount and tax per rate; empty if no line has a tax rate
+    TaxSummary {
+        width: i32,
+    },
+    KeyValues {
+        rows: Vec<(String, String)>,
+        label_width: i32,
+        line_height: i32,
+    },
+    Notes {
+        title: String,
+        max_chars: usize,
+    },
+    // Payment terms, dates and bank accounts; empty without `Order::payment`
+    Payment,
+    // Customer number, PO number, contact person and cost center, one per line
+    HeaderFields,
+    // `Order::custom_fields`, continued in a second column after `max_rows` lines
+    CustomFields {
+        max_rows: usize,
+        column_width: i32,
+    },
+}
+
+impl BlockKind {
+    /// The block's `type`, as written in the template
+    fn name(&self) -> &'static str {
+        match self {
+            BlockKind::Text { .. } => "text",
+            BlockKind::Image { .. } => "image",
+            BlockKind::Address { .. } => "address",
+            BlockKind::Line { .. } => "line",
+            BlockKind::ItemsTable { .. } => "items_table",
+            BlockKind::Totals { .. } => "totals",
+            BlockKind::TaxSummary { .. } => "tax_summary",
+            BlockKind::KeyValues { .. } => "key_values",
+            BlockKind::Notes { .. } => "notes",
+            BlockKind::Payment => "payment",
+            BlockKind::HeaderFields => "header_fields",
+            BlockKind::CustomFields { .. } => "custom_fields",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Block {
+    #[serde(default = "default_x")]
+    pub x: i32,
+    pub y: Option<i32>,
+    #[serde(default)]
+    pub gap: i32,
+    // Start at the top of the previous block instead of below it, e.g. two tables side by side
+    #[serde(default)]
+    pub beside: bool,
+    // With `y`: moves down to `gap` below the previous block if that ends lower
+    #[serde(default)]
+    pub below: bool,
+    // Move to a new page together with the next block, e.g. a title with its lines
+    #[serde(default)]
+    pub keep_with_next: bool,
+    pub when: Option<Condition>,
+    #[serde(flatten)]
+    pub kind: BlockKind,
+}
+
+fn default_x() -> i32 {
+    50
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AddressesDefinition {
+    pub shipping_title: String,
+    pub billing_title: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DeclarativeTemplate {
+    pub title: String,
+    #[serde(default)]
+    pub header: Vec<Block>,
+    // None = "Ship To" / "Bill To"
+    pub addresses: Option<AddressesDefinition>,
+    #[serde(default)]
+    pub body: Vec<Block>,
+    #[serde(default)]
+    pub footer: Vec<Block>,
+}
+
+impl DeclarativeTemplate {
+    pub fn from_json(json: &str) -> Result<Self, PdfError> {
+        let template: DeclarativeTemplate =
+            serde_json::from_str(json).map_err(|e| PdfError::Template(e.to_string()))?;
+        template.check()?;
+        Ok(template)
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, PdfError> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json)
+    }
+
+    /// The built-in invoice layout, as shipped in `templates/invoice.json`
+    pub fn invoice() -> Result<Self, PdfError> {
+        Self::from_json(include_str!("../../templates/invoice.json"))
+    }
+
+    /// The built-in proforma invoice layout, as shipped in `templates/proforma_invoice.json`
+    pub fn proforma_invoice() -> Result<Self, PdfError> {
+        Self::from_json(include_str!("../../templates/proforma_invoice.json"))
+    }
+
+    /// The built-in packing list layout, as shipped in `templates/packing_list.json`
+    pub fn packing_list() -> Result<Self, PdfError> {
+        Self::from_json(include_str!("../../templates/packing_list.json"))
+    }
+
+    /// Verifies all placeholders refer to known labels and fields
+    fn check(&self) -> Result<(), PdfError> {
+        if let Some(addresses) = &self.addresses {
+            check_placeholders(&addresses.shipping_title, false)?;
+            check_placeholders(&addresses.billing_title, false)?;
+        }
+
+        for block in self.header.iter().chain(&self.body).chain(&self.footer) {
+            match &block.kind {
+                BlockKind::Text { text, .. } => check_placeholders(text, false)?,
+                BlockKind::Address { title, .. } | BlockKind::Notes { title, .. } => {
+                    check_placeholders(title, false)?
+                }
+                BlockKind::ItemsTable { columns } => {
+                    for column in columns {
+                        check_placeholders(&column.header, false)?;
+                        check_placeholders(&column.value, true)?;
+                    }
+                }
+                BlockKind::Totals { rows, .. } | BlockKind::KeyValues { rows, .. } => {
+                    for (label, value) in rows {
+                        check_placeholders(label, false)?;
+                        check_placeholders(value, false)?;
+                    }
+                }
+                BlockKind::Image { .. }
+                | BlockKind::Line { .. }
+                | BlockKind::TaxSummary { .. }
+                | BlockKind::Payment
+                | BlockKind::HeaderFields
+                | BlockKind::CustomFields { .. } => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws `blocks` from `start_y` down; returns the operations and the y position
+    /// the next block flows below
+    fn draw_blocks<'b>(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+        blocks: impl IntoIterator<Item = &'b Block>,
+        start_y: i32,
+    ) -> Result<(Vec<Op>, i32), PdfError> {
+        let mut ops = vec![];
+        let mut current_y = start_y;
+        // Top of the previously drawn block, for `beside`
+        let mut previous_y = start_y;
+
+        for block in blocks {
+            if !condition_met(block.when, ctx) {
+                continue;
+            }
+
+            let y = match block.y {
+                Some(y) if block.below => y.min(current_y - block.gap),
+                Some(y) => y,
+                None if block.beside => previous_y - block.gap,
+                None => current_y - block.gap,
+            };
+            let (block_ops, height) = self.draw_block(doc, ctx, block, y)?;
+            ops.extend(block_ops);
+            current_y = if block.beside {
+                current_y.min(y - height)
+            } else {
+                y - height
+            };
+            previous_y = y;
+        }
+
+        Ok((ops, current_y))
+    }
+
+    fn draw_block(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+        block: &Block,
+        y: i32,
+    ) -> Result<(Vec<Op>, i32), PdfError> {
+        let properties = ctx.properties;
+        let x = block.x;
+
+        let drawn = match &block.kind {
+            BlockKind::Text { text, bold, size } => {
+                let text = fill_placeholders(text, ctx, None);
+                let font_size = match size {
+                    FontSize::Title => properties.font_size_title,
+                    FontSize::Body => properties.font_size_body,
+                    FontSize::Label => properties.font_size_label,
+                };
+                let ops = if *bold {
+                    draw_bold_text(x, y, &text, font_size, ctx.fonts)
+                } else {
+                    draw_text(x, y, &text, font_size, ctx.fonts)
+                };
+                (ops, 0)
+            }
+            BlockKind::Image {
+                source,
+                width_mm,
+                height_mm,
+            } => {
+                let data = match source {
+                    ImageSource::Logo => ctx.logo_data,
+                    ImageSource::Signature => properties.signature_image.as_deref(),
+                    ImageSource::Seal => properties.seal_image.as_deref(),
+                };
+                (
+                    draw_logo(doc, x, y, data, *width_mm, *height_mm, ctx.fonts)?,
+                    0,
+                )
+            }
+            BlockKind::Address { title, source } => {
+                let address: &Address = match source {
+                    AddressSource::Warehouse => ctx.warehouse_address,
+                    AddressSource::Shipping => &ctx.order.shipping_address,
+                    AddressSource::Billing => &ctx.order.billing_address,
+                };
+                let title = fill_placeholders(title, ctx, None);
+                AddressBlock {
+                    title: &title,
+                    address,
+                    translation: ctx.translation,
+                }
+                .draw(x, y, properties, ctx.fonts)
+            }
+            BlockKind::Line { width } => match width {
+                Some(width) => (vec![draw_line(x, x + width, y)], 0),
+                None => (vec![draw_horizontal_line(y)], 0),
+            },
+            BlockKind::ItemsTable { columns } => {
+                items_table(columns, ctx).draw(x, y, properties, ctx.fonts)
+            }
+            BlockKind::Totals { width, rows } => TotalsTable {
+                rows: fill_totals_rows(rows, ctx),
+                width: *width,
+            }
+            .draw(x, y, properties, ctx.fonts),
+            BlockKind::TaxSummary { width } => {
+                TaxSummaryTable::for_order(ctx.order, ctx.order_items, ctx.translation, *width)
+                    .draw(x, y, properties, ctx.fonts)
+            }
+            BlockKind::KeyValues {
+                rows,
+                label_width,
+                line_height,
+            } => KeyValueBlock {
+                rows: fill_rows(rows, ctx),
+                label_width: *label_width,
+                line_height: *line_height,
+            }
+            .draw(x, y, properties, ctx.fonts),
+            BlockKind::Notes { title, max_chars } => match &ctx.order.notes {
+                Some(notes) => NotesBlock {
+                    title: fill_placeholders(title, ctx, None),
+                    text: notes.clone(),
+                    max_chars: *max_chars,
+                }
+                .draw(x, y, properties, ctx.fonts),
+                None => (vec![], 0),
+            },
+            BlockKind::Payment => match &ctx.order.payment {
+                Some(payment) => PaymentBlock::for_payment(payment, ctx.translation)
+                    .draw(x, y, properties, ctx.fonts),
+                None => (vec![], 0),
+            },
+            BlockKind::HeaderFields => TextLines {
+                lines: header_fields(ctx.order, ctx.translation),
+                line_height: 20,
+            }
+            .draw(x, y, properties, ctx.fonts),
+            BlockKind::CustomFields {
+                max_rows,
+                column_width,
+            } => ColumnLines {
+                lines: custom_field_lines(ctx.order),
+                line_height: 20,
+                max_rows: *max_rows,
+                column_width: *column_width,
+            }
+            .draw(x, y, properties, ctx.fonts),
+        };
+
+        Ok(drawn)
+    }
+}
+
+impl DocumentTemplate for DeclarativeTemplate {
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// True with a totals, tax summary or payment block
+    fn has_amounts(&self) -> bool {
+        self.header
+            .iter()
+            .chain(&self.body)
+            .chain(&self.footer)
+            .any(|block| {
+                matches!(
+                    block.kind,
+                    BlockKind::Totals { .. } | BlockKind::TaxSummary { .. } | BlockKind::Payment
+                )
+            })
+    }
+
+    fn draw_header(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+    ) -> Result<(Vec<Op>, i32), PdfError> {
+        let (ops, end_y) = self.draw_blocks(doc, ctx, &self.header, 790)?;
+        ensure_fits("header", end_y, ctx.properties, ctx.translation)?;
+        Ok((ops, end_y.min(HEADER_BOTTOM)))
+    }
+
+    fn draw_addresses(&self, ctx: &TemplateContext, header_y: i32) -> (Vec<Op>, i32) {
+        let (shipping_title, billing_title) = match &self.addresses {
+            Some(addresses) => (
+                fill_placeholders(&addresses.shipping_title, ctx, None),
+                fill_placeholders(&addresses.billing_title, ctx, None),
+            ),
+            None => (
+                ctx.translation.ship_to_label.clone(),
+                ctx.translation.bill_to_label.clone(),
+            ),
+        };
+        draw_addresses(
+            ctx.properties,
+            ctx.translation,
+            &ctx.order.shipping_address,
+            &ctx.order.billing_address,
+            &shipping_title,
+            &billing_title,
+            header_y - 20,
+            ctx.fonts,
+        )
+    }
+
+    /// Blocks move to a new page in groups: a block with the `beside` blocks after it,
+    /// joined by `keep_with_next`. An items table on its own continues on new pages;
+    /// blocks with `y` stay where they are.
+    fn draw_body(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+        flow: &mut PageFlow,
+    ) -> Result<(), PdfError> {
+        let blocks: Vec<&Block> = self
+            .body
+            .iter()
+            .filter(|block| condition_met(block.when, ctx))
+            .collect();
+        let mut start = 0;
+        while start < blocks.len() {
+            let mut end = start + 1;
+            while end < blocks.len() && (blocks[end].beside || blocks[end - 1].keep_with_next) {
+                end += 1;
+            }
+            let group = &blocks[start..end];
+            let first = group[0];
+            match (group, &first.kind) {
+                (_, _) if first.y.is_some() => {
+                    let (ops, end_y) = self.draw_blocks(doc, ctx, group.iter().copied(), flow.y)?;
+                    let height = flow.y - end_y;
+                    flow.push(ops, height);
+                }
+                ([_], BlockKind::ItemsTable { columns }) => {
+                    items_table(columns, ctx).draw_flow(
+                        first.x,
+                        first.gap,
+                        flow,
+                        ctx.properties,
+                        ctx.fonts,
+                    )?;
+                }
+                _ => flow.place(first.kind.name(), first.gap, |y| {
+                    let (ops, end_y) =
+                        self.draw_blocks(doc, ctx, group.iter().copied(), y + first.gap)?;
+                    Ok((ops, y - end_y))
+                })?,
+            }
+            start = end;
+        }
+        Ok(())
+    }
+
+    fn draw_footer(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+    ) -> Result<Vec<Op>, PdfError> {
+        // Starts 40 points above the bottom margin, or the seller footer
+        let start_y = content_bottom(ctx.properties, ctx.translation) + 40;
+        let (ops, end_y) = self.draw_blocks(doc, ctx, &self.footer, start_y)?;
+        ensure_fits("footer", end_y, ctx.properties, ctx.translation)?;
+        Ok(ops)
+    }
+}
+
+/// The items table with the columns whose condition is met, one row per line item
+fn items_table(columns: &[ColumnDefinition], ctx: &TemplateContext) -> ItemsTable {
+    let columns: Vec<&ColumnDefinition> = columns
+        .iter()
+        .filter(|column| condition_met(column.when, ctx))
+        .collect();
+    ItemsTable {
+        columns: columns
+            .iter()
+            .map(|column| {
+                let align = match column.align {
+                    ColumnAlign::Left => Align::Left,
+                    ColumnAlign::Right => Align::Right,
+                };
+                TableColumn {
+                    max_chars: column.max_chars,
+                    ..TableColumn::new(
+                        &fill_placeholders(&column.header, ctx, None),
+                        column.x,
+                        column.width,
+                        align,
+                    )
+                }
+            })
+            .collect(),
+        rows: ctx
+            .order_items
+            .iter()
+            .map(|item| {
+                columns
+                    .iter()
+                    .map(|column| {
+                        if column.checkbox {
+                            TableCell::Checkbox
+                        } else {
+                            fill_placeholders(&column.value, ctx, Some(item)).into()
+                        }
+                    })
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+fn condition_met(condition: Option<Condition>, ctx: &TemplateContext) -> bool {
+    let properties = ctx.properties;
+    match condition {
+        None => true,
+        Some(Condition::Notes) => ctx.order.notes.is_some(),
+        Some(Condition::Payment) => ctx.order.payment.is_some(),
+        Some(Condition::SupplyDate) => supply_date_text(ctx.order, ctx.translation).is_some(),
+        Some(Condition::TaxTreatmentNotice) => {
+            tax_treatment_notice(ctx.order, properties, ctx.translation).is_some()
+        }
+        Some(Condition::Tax) => properties.small_business_exemption.is_none(),
+        Some(Condition::Signature) => properties.signature_image.is_some(),
+        Some(Condition::Seal) => properties.seal_image.is_some(),
+        Some(Condition::SignatureOrSeal) => {
+            properties.signature_image.is_some() || properties.seal_image.is_some()
+        }
+    }
+}
+
+fn fill_rows(rows: &[(String, String)], ctx: &TemplateContext) -> Vec<(String, String)> {
+    rows.iter()
+        .map(|(label, value)| {
+            (
+                fill_placeholders(label, ctx, None),
+                fill_placeholders(value, ctx, None),
+            )
+        })
+        .collect()
+}
+
+/// Like [`fill_rows`], but a row showing `{order.tax_total}` becomes one row per tax line.
+/// With gross pricing, the included tax is listed after `{order.total}` instead; with the
+/// small-business exemption, there is none.
+fn fill_totals_rows(rows: &[(String, String)], ctx: &TemplateContext) -> Vec<(String, String)> {
+    let show_tax = ctx.properties.small_business_exemption.is_none();
+    let gross = ctx.order.pricing_mode == PricingMode::Gross;
+    let mut filled = vec![];
+    for row in rows {
+        match row.1.as_str() {
+            "{order.tax_total}" if gross || !show_tax => {}
+            "{order.tax_total}" => filled.extend(tax_rows(ctx.order, ctx.translation)),
+            value => {
+                filled.extend(fill_rows(std::slice::from_ref(row), ctx));
+                if show_tax && gross && value == "{order.total}" {
+                    filled.extend(included_tax_rows(
+                        ctx.order,
+                        ctx.order_items,
+                        ctx.translation,
+                    ));
+                }
+            }
+        }
+    }
+    filled
+}
+
+/// Splits `text` into literal text and `{namespace.key}` placeholders
+fn for_each_placeholder<F>(text: &str, mut f: F) -> Result<String, PdfError>
+where
+    F: FnMut(&str, &str) -> Result<String, PdfError>,
+{
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| PdfError::Template(format!("Unclosed placeholder in '{}'", text)))?;
+        let placeholder = &rest[start + 1..start + end];
+        let (namespace, key) = placeholder.split_once('.').ok_or_else(|| {
+            PdfError::Template(format!("Invalid placeholder '{{{}}}'", placeholder))
+        })?;
+        output.push_str(&f(namespace, key)?);
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+fn check_placeholders(text: &str, allow_item: bool) -> Result<(), PdfError> {
+    let dictionary = Dictionary::default();
+    for_each_placeholder(text, |namespace, key| {
+        let known = match namespace {
+            "label" => dictionary.label(key).is_some(),
+            "order" => ORDER_FIELDS.contains(&key),
+            "item" if allow_item => ITEM_FIELDS.contains(&key),
+            _ => false,
+        };
+        if known {
+            Ok(String::new())
+        } else {
+            Err(PdfError::Template(format!(
+                "Unknown placeholder '{{{}.{}}}'",
+                namespace, key
+            )))
+        }
+    })
+    .map(|_| ())
+}
+
+/// Placeholders are verified when the template is loaded, so unknown keys render empty
+fn fill_placeholders(text: &str, ctx: &TemplateContext, item: Option<&OrderLineItem>) -> String {
+    for_each_placeholder(text, |namespace, key| {
+        Ok(match (namespace, item) {
+            ("label", _) => ctx.translation.label(key).unwrap_or_default().to_string(),
+            ("order", _) => order_field(ctx, key),
+            ("item", Some(item)) => item_field(item, ctx.order, key),
+            _ => String::new(),
+        })
+    })
+    .unwrap_or_default()
+}
+
+/// Subtotals and shipping include tax with gross pricing
+fn order_field(ctx: &TemplateContext, key: &str) -> String {
+    let order = ctx.order;
+    let currency = &order.currency;
+    if order.pricing_mode == PricingMode::Gross {
+        let gross = order.gross_totals(ctx.order_items);
+        let amount = match key {
+            "shipping_total" => Some(gross.shipping_total),
+            "subtotal_before_discount" => Some(gross.subtotal_before_discount),
+            "discount_total" => Some(gross.discount_total),
+            "subtotal" => Some(gross.subtotal),
+            _ => None,
+        };
+        if let Some(amount) = amount {
+            return format_decimal(amount, currency);
+        }
+    }
+    match key {
+        "id" => order.id.clone(),
+        "currency" => order.currency.clone(),
+        "status" => order_status_text(order, ctx.translation).to_string(),
+        "shipping_method" => order.shipping_method.clone(),
+        "shipping_total" => format_decimal(order.shipping_total, currency),
+        "shipping_tax_rate" => format_rate(order.shipping_tax_rate),
+        "subtotal_before_discount" => format_decimal(order.subtotal_before_discount, currency),
+        "discount_total" => format_decimal(order.discount_total, currency),
+        "subtotal" => format_decimal(order.subtotal, currency),
+        "tax_total" => format_decimal(order.tax_total, currency),
+        "total" => format_decimal(order.total, currency),
+        "notes" => order.notes.clone().unwrap_or_default(),
+        "tax_treatment_notice" => tax_treatment_notice(order, ctx.properties, ctx.translation)
+            .unwrap_or_default()
+            .to_string(),
+        "invoice_number" => order.invoice_number().to_string(),
+        "invoice_date" => format_date(order.invoice_date()),
+        "supply_date" => supply_date_text(order, ctx.translation).unwrap_or_default(),
+        "customer_number" => order.customer_number.clone().unwrap_or_default(),
+        "customer_reference" => order.customer_reference.clone().unwrap_or_default(),
+        "contact_person" => order.contact_person.clone().unwrap_or_default(),
+        "cost_center" => order.cost_center.clone().unwrap_or_default(),
+        "created_at" => format_date(order.created_at.date()),
+        "updated_at" => format_date(order.updated_at.date()),
+        "total_items" => ctx
+            .order_items
+            .iter()
+            .map(|item| item.quantity)
+            .sum::<i64>()
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+/// "19%", or empty without a rate
+fn format_rate(rate: Option<Decimal>) -> String {
+    rate.map(|rate| format!("{}%", rate.normalize()))
+        .unwrap_or_default()
+}
+
+/// Prices, discounts and subtotals include tax with gross pricing
+fn item_field(item: &OrderLineItem, order: &Order, key: &str) -> String {
+    let currency = &order.currency;
+    if order.pricing_mode == PricingMode::Gross {
+        let amount = match key {
+            "unit_price" => Some(item.gross_unit_price()),
+            "discount_total" => Some(item.gross_discount_total()),
+            "subtotal_before_discount" => Some(item.total + item.gross_discount_total()),
+            "subtotal" => Some(item.total),
+            _ => None,
+        };
+        if let Some(amount) = amount {
+            return format_decimal(amount, currency);
+        }
+    }
+    match key {
+        "id" => item.id.clone(),
+        "title" => item.title.clone(),
+        "sku" => item.sku.clone().unwrap_or_else(|| "N/A".to_string()),
+        "title_sku" => match &item.sku {
+            Some(sku) if !sku.is_empty() => format!("{} [{}]", item.title, sku),
+            _ => item.title.clone(),
+        },
+        "quantity" => item.quantity.to_string(),
+        "unit_price" => format_decimal(item.unit_price, currency),
+        "unit_tax" => format_decimal(item.unit_tax, currency),
+        "unit_discount" => format_decimal(item.unit_discount, currency),
+        "subtotal_before_discount" => format_decimal(item.subtotal_before_discount, currency),
+        "discount_total" => format_decimal(item.discount_total, currency),
+        "subtotal" => format_decimal(item.subtotal, currency),
+        "tax_total" => format_decimal(item.tax_total, currency),
+        "total" => format_decimal(item.total, currency),
+        "tax_rate" => format_rate(item.tax_rate),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::Calculator;
+    use crate::templates::pdf_utils::load_fonts;
+    use crate::test_utils::{address, line, order, properties};
+    use crate::types::{InvoiceMeta, Language};
+
+    fn template_error(json: &str) -> String {
+        match DeclarativeTemplate::from_json(json) {
+            Err(PdfError::Template(message)) => message,
+            other => panic!(
+                "expected a template error, got {:?}",
+                other.map(|t| t.title)
+            ),
+        }
+    }
+
+    fn body(block: &str) -> String {
+        format!(r#"{{ "title": "Test", "body": [{}] }}"#, block)
+    }
+
+    #[test]
+    fn loads_the_built_in_templates() {
+        for template in [
+            DeclarativeTemplate::invoice().unwrap(),
+            DeclarativeTemplate::proforma_invoice().unwrap(),
+            DeclarativeTemplate::packing_list().unwrap(),
+        ] {
+            assert!(!template.body.is_empty(), "{}", template.title);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        // Not JSON, unknown block type, missing field
+        template_error("{ \"title\": ");
+        template_error(&body(r#"{ "type": "chart" }"#));
+        template_error(&body(r#"{ "type": "notes", "title": "Notes" }"#));
+
+        let message = template_error(&body(
+            r#"{ "type": "text", "text": "{label.no_such_label}" }"#,
+        ));
+        assert!(message.contains("{label.no_such_label}"), "{}", message);
+        let message = template_error(&body(r#"{ "type": "text", "text": "{order.password}" }"#));
+        assert!(message.contains("{order.password}"), "{}", message);
+        // Item fields only exist in table cells
+        template_error(&body(r#"{ "type": "text", "text": "{item.title}" }"#));
+        template_error(&body(r#"{ "type": "text", "text": "{order.id" }"#));
+        template_error(&body(r#"{ "type": "text", "text": "{id}" }"#));
+        template_error(
+            r#"{ "title": "Test", "addresses": { "shipping_title": "{label.nope}", "billing_title": "" } }"#,
+        );
+    }
+
+    #[test]
+    fn accepts_item_fields_in_table_cells() {
+        let template = DeclarativeTemplate::from_json(&body(
+            r#"{ "type": "items_table", "columns": [
+                { "header": "{label.product_header}", "x": 0, "width": 180, "value": "{item.title_sku}" }
+            ] }"#,
+        ))
+        .unwrap();
+        let block = &template.body[0];
+        assert_eq!((block.x, block.y, block.gap), (50, None, 0));
+        assert!(!block.beside && !block.below && !block.keep_with_next);
+        assert!(!template.has_amounts());
+    }
+
+    #[test]
+    fn has_amounts_with_totals_tax_or_payment() {
+        for block in [
+            r#"{ "type": "totals", "width": 200, "rows": [["Total", "{order.total}"]] }"#,
+            r#"{ "type": "tax_summary", "width": 200 }"#,
+            r#"{ "type": "payment" }"#,
+        ] {
+            assert!(DeclarativeTemplate::from_json(&body(block))
+                .unwrap()
+                .has_amounts());
+        }
+        assert!(!DeclarativeTemplate::packing_list().unwrap().has_amounts());
+        assert!(DeclarativeTemplate::invoice().unwrap().has_amounts());
+    }
+
+    #[test]
+    fn fills_placeholders() {
+        let (mut order, items) = order(
+            &Calculator::default(),
+            &[line("1", 2, Decimal::new(1050, 2), Decimal::from(19))],
+            Decimal::new(495, 2),
+            Decimal::from(19),
+        );
+        order.invoice = Some(InvoiceMeta {
+            number: "2024-0042".to_string(),
+            issue_date: chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            supply_date: None,
+        });
+        let properties = properties().input_or_default();
+        let translation = Dictionary::for_language(Language::German);
+        let warehouse = address("Lager");
+        let mut doc = PdfDocument::new("Test");
+        let fonts = load_fonts(&mut doc, Some(Language::German), None, None).unwrap();
+        let ctx = TemplateContext {
+            order: &order,
+            order_items: &items,
+            warehouse_address: &warehouse,
+            properties: &properties,
+            translation: &translation,
+            fonts: &fonts,
+            logo_data: None,
+        };
+
+        assert_eq!(
+            fill_placeholders(
+                "{label.invoice_number_prefix}{order.invoice_number} ({order.invoice_date})",
+                &ctx,
+                None
+            ),
+            format!(
+                "{}2024-0042 (2024-03-04)",
+                translation.invoice_number_prefix
+            )
+        );
+        assert_eq!(
+            fill_placeholders(
+                "{order.total_items} / {order.shipping_tax_rate}",
+                &ctx,
+                None
+            ),
+            "2 / 19%"
+        );
+        assert_eq!(
+            fill_placeholders(
+                "{item.title_sku}: {item.quantity} x {item.unit_price}",
+                &ctx,
+                Some(&items[0])
+            ),
+            "Item 1: 2 x EUR 10.50"
+        );
+        // Item fields are empty outside a table row
+        assert_eq!(fill_placeholders("[{item.title}]", &ctx, None), "[]");
+        assert_eq!(
+            fill_placeholders("{order.total}", &ctx, None),
+            format_decimal(order.total, "EUR")
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Problems found while generating a document that did not stop generation,
+//! e.g. truncated text or oversized images. Returned as a [`GenerationReport`].
+
+use printpdf::{PdfParseErrorSeverity, PdfWarnMsg};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    // Text was drawn with the fallback font
+    FontFallback,
+    // No font has a glyph for a character of the drawn text; it shows as an empty box
+    MissingGlyph,
+    // Text was cut off to fit its column
+    TextTruncated,
+    // Image is larger than the space reserved for it
+    ImageOversized,
+    // Warning reported by printpdf while parsing fonts, decoding images or saving
+    PrintPdf,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Collects diagnostics while a document is drawn. Identical entries are recorded once.
+#[derive(Debug, Default)]
+pub struct Diagnostics(Mutex<Vec<Diagnostic>>);
+
+impl Diagnostics {
+    pub fn push(&self, kind: DiagnosticKind, severity: Severity, message: impl Into<String>) {
+        let diagnostic = Diagnostic {
+            kind,
+            severity,
+            message: message.into(),
+        };
+        let mut diagnostics = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if !diagnostics.contains(&diagnostic) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Records printpdf warnings; purely informational messages are skipped
+    pub fn extend_printpdf(&self, warnings: Vec<PdfWarnMsg>) {
+        for warning in warnings {
+            // Glyphs without outline data (spaces, composites) are still embedded
+            if warning.msg.starts_with("Failed to convert glyph") {
+                continue;
+            }
+            let severity = match warning.severity {
+                PdfParseErrorSeverity::Info => continue,
+                PdfParseErrorSeverity::Warning => Severity::Warning,
+                PdfParseErrorSeverity::Error => Severity::Error,
+            };
+            self.push(DiagnosticKind::PrintPdf, severity, warning.msg);
+        }
+    }
+
+    /// The first entry of `kind` collected so far
+    pub fn first(&self, kind: DiagnosticKind) -> Option<Diagnostic> {
+        let diagnostics = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.kind == kind)
+            .cloned()
+    }
+
+    /// Removes and returns everything collected so far
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    pub fn into_report(self) -> GenerationReport {
+        GenerationReport {
+            diagnostics: self.0.into_inner().unwrap_or_else(|e| e.into_inner()),
+        }
+    }
+}
+
+/// Everything noteworthy that happened while generating a document.
+#[derive(Clone, Debug, Default)]
+pub struct GenerationReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl GenerationReport {
+    /// True if there is nothing worse than [`Severity::Info`]
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Info)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity >= Severity::Warning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_identical_entries_once() {
+        let diagnostics = Diagnostics::default();
+        for _ in 0..3 {
+            diagnostics.push(DiagnosticKind::TextTruncated, Severity::Warning, "title");
+        }
+        // Same message, different kind or severity
+        diagnostics.push(DiagnosticKind::MissingGlyph, Severity::Warning, "title");
+        diagnostics.push(DiagnosticKind::TextTruncated, Severity::Error, "title");
+        diagnostics.push(DiagnosticKind::TextTruncated, Severity::Warning, "notes");
+
+        let report = diagnostics.into_report();
+        assert_eq!(report.diagnostics.len(), 4);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn take_starts_over() {
+        let diagnostics = Diagnostics::default();
+        diagnostics.push(DiagnosticKind::FontFallback, Severity::Info, "ไทย");
+        assert_eq!(diagnostics.take().len(), 1);
+        diagnostics.push(DiagnosticKind::FontFallback, Severity::Info, "ไทย");
+        let report = diagnostics.into_report();
+        assert_eq!(report.diagnostics.len(), 1);
+        assert!(report.is_clean());
+        assert_eq!(report.warnings().count(), 0);
+    }
+
+    #[test]
+    fn skips_informational_printpdf_warnings() {
+        let diagnostics = Diagnostics::default();
+        diagnostics.extend_printpdf(vec![
+            PdfWarnMsg::info(0, 0, "Parsed font".to_string()),
+            PdfWarnMsg::warning(0, 1, "Failed to convert glyph 3".to_string()),
+            PdfWarnMsg::warning(0, 2, "Unsupported image filter".to_string()),
+            PdfWarnMsg::warning(1, 7, "Unsupported image filter".to_string()),
+            PdfWarnMsg::error(0, 3, "Broken font table".to_string()),
+        ]);
+        let report = diagnostics.into_report();
+        let messages: Vec<_> = report
+            .warnings()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (Severity::Warning, "Unsupported image filter"),
+                (Severity::Error, "Broken font table"),
+            ]
+        );
+    }
+}
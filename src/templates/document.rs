@@ -0,0 +1,403 @@
+use crate::types::{
+    Address, Dictionary, DocumentProperties, DocumentPropertiesDefault, Order, OrderLineItem,
+};
+use printpdf::{Mm, Op, PdfDocument, PdfPage, PdfSaveOptions};
+use std::io::{self, Write};
+
+use super::components::{Component, SellerFooter};
+use super::diagnostics::{DiagnosticKind, Diagnostics, GenerationReport};
+use super::errors::PdfError;
+use super::pdf_utils::{
+    draw_addresses, draw_watermark, load_fonts, watermark_graphics_state, FontBundle,
+};
+
+/// Lowest y position (in points) content may reach
+pub const PAGE_BOTTOM_MARGIN: i32 = 20;
+
+/// Highest y position of the separator line below the header; longer headers push it down
+pub const HEADER_BOTTOM: i32 = 630;
+
+/// Lowest y position content may reach: the bottom margin, or above the seller footer
+/// with its labels in `translation`.
+pub fn content_bottom(pdf_properties: &DocumentPropertiesDefault, translation: &Dictionary) -> i32 {
+    match &pdf_properties.seller {
+        Some(seller) => {
+            PAGE_BOTTOM_MARGIN
+                + SellerFooter::for_seller(seller, translation).height(pdf_properties)
+                + 10
+        }
+        None => PAGE_BOTTOM_MARGIN,
+    }
+}
+
+/// Fails with [`PdfError::LayoutOverflow`] if `section`, ending at `y`, runs past
+/// [`content_bottom`].
+pub fn ensure_fits(
+    section: &str,
+    y: i32,
+    pdf_properties: &DocumentPropertiesDefault,
+    translation: &Dictionary,
+) -> Result<(), PdfError> {
+    let bottom = content_bottom(pdf_properties, translation);
+    if y < bottom {
+        return Err(PdfError::LayoutOverflow {
+            section: section.to_string(),
+            overflow: bottom - y,
+        });
+    }
+    Ok(())
+}
+
+/// Highest y position of content on pages after the first, which have no header
+pub const CONTINUATION_TOP: i32 = 790;
+
+/// Body content laid out top to bottom, continued on a new page when it would run
+/// past [`content_bottom`].
+pub struct PageFlow {
+    // Operations of the finished pages
+    pages: Vec<Vec<Op>>,
+    // Operations of the current page
+    pub ops: Vec<Op>,
+    // Where the next content starts on the current page
+    pub y: i32,
+    bottom: i32,
+    // Nothing was placed on the current page since it was started
+    at_top: bool,
+}
+
+impl PageFlow {
+    /// Continues the first page, which has `ops` so far, at `y`
+    pub fn new(
+        ops: Vec<Op>,
+        y: i32,
+        pdf_properties: &DocumentPropertiesDefault,
+        translation: &Dictionary,
+    ) -> Self {
+        Self {
+            pages: vec![],
+            ops,
+            y,
+            bottom: content_bottom(pdf_properties, translation),
+            at_top: false,
+        }
+    }
+
+    /// Lowest y position content may reach on every page
+    pub fn bottom(&self) -> i32 {
+        self.bottom
+    }
+
+    /// True on a new page nothing was placed on yet
+    pub fn at_top(&self) -> bool {
+        self.at_top
+    }
+
+    /// Finishes the current page; content continues at [`CONTINUATION_TOP`]
+    pub fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.ops));
+        self.y = CONTINUATION_TOP;
+        self.at_top = true;
+    }
+
+    /// Adds content drawn at the current position, `height` points tall
+    pub fn push(&mut self, ops: Vec<Op>, height: i32) {
+        self.ops.extend(ops);
+        self.y -= height;
+        self.at_top = false;
+    }
+
+    /// Draws a block that is not split across pages `gap` points below the current
+    /// position. `draw` gets the y position and returns the operations and the height.
+    /// A block running past the bottom is drawn again at the top of a new page, without
+    /// the gap; if it doesn't fit there either, fails with [`PdfError::LayoutOverflow`].
+    pub fn place<F>(&mut self, section: &str, gap: i32, mut draw: F) -> Result<(), PdfError>
+    where
+        F: FnMut(i32) -> Result<(Vec<Op>, i32), PdfError>,
+    {
+        let gap = if self.at_top { 0 } else { gap };
+        let (ops, height) = draw(self.y - gap)?;
+        if self.y - gap - height < self.bottom && !self.at_top {
+            self.new_page();
+            return self.place(section, 0, draw);
+        }
+        if self.y - gap - height < self.bottom {
+            return Err(PdfError::LayoutOverflow {
+                section: section.to_string(),
+                overflow: self.bottom - (self.y - gap - height),
+            });
+        }
+        self.y -= gap;
+        self.push(ops, height);
+        Ok(())
+    }
+
+    /// Operations of every page, the current one last
+    pub fn into_pages(mut self) -> Vec<Vec<Op>> {
+        self.pages.push(self.ops);
+        self.pages
+    }
+}
+
+/// Everything a template needs to lay out a document.
+pub struct TemplateContext<'a> {
+    pub order: &'a Order,
+    pub order_items: &'a [OrderLineItem],
+    pub warehouse_address: &'a Address,
+    pub properties: &'a DocumentPropertiesDefault,
+    pub translation: &'a Dictionary,
+    pub fonts: &'a FontBundle,
+    pub logo_data: Option<&'a [u8]>,
+}
+
+/// A document layout, rendered top to bottom: header, addresses, body and footer.
+///
+/// Implement this to create custom document types; render them with [`generate_pdf`].
+pub trait DocumentTemplate {
+    /// Title stored in the PDF metadata, e.g. "Invoice"
+    fn title(&self) -> &str;
+
+    /// Whether the document shows prices and totals. Documents without them, like
+    /// packing lists, are not checked with [`Order::validate`].
+    fn has_amounts(&self) -> bool {
+        true
+    }
+
+    /// Title, logo, sender address and document info. Returns the operations and
+    /// the y position of the separator line below them, at most [`HEADER_BOTTOM`].
+    fn draw_header(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+    ) -> Result<(Vec<Op>, i32), PdfError>;
+
+    /// Shipping and billing addresses, starting 20 points below the header line at
+    /// `header_y`. Returns the operations and the y position of the separator line below them.
+    fn draw_addresses(&self, ctx: &TemplateContext, header_y: i32) -> (Vec<Op>, i32) {
+        draw_addresses(
+            ctx.properties,
+            ctx.translation,
+            &ctx.order.shipping_address,
+            &ctx.order.billing_address,
+            &ctx.translation.ship_to_label,
+            &ctx.translation.bill_to_label,
+            header_y - 20,
+            ctx.fonts,
+        )
+    }
+
+    /// Main content (items, totals, notes), starting at `flow.y` and continued on
+    /// new pages as needed
+    fn draw_body(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+        flow: &mut PageFlow,
+    ) -> Result<(), PdfError>;
+
+    /// Content at the bottom of the last page
+    fn draw_footer(
+        &self,
+        _doc: &mut PdfDocument,
+        _ctx: &TemplateContext,
+    ) -> Result<Vec<Op>, PdfError> {
+        Ok(vec![])
+    }
+}
+
+/// Renders `template` into pages for `doc`, reusing the fonts in `ctx`.
+///
+/// Images are registered with `doc`; the pages are returned rather than added,
+/// so they can be combined with other documents or custom pages.
+pub fn render_pages<T: DocumentTemplate + ?Sized>(
+    doc: &mut PdfDocument,
+    template: &T,
+    ctx: &TemplateContext,
+) -> Result<Vec<PdfPage>, PdfError> {
+    if ctx.properties.validate && template.has_amounts() {
+        if let Some(problem) = ctx.order.validate(ctx.order_items).into_iter().next() {
+            return Err(problem.into());
+        }
+        if ctx.properties.small_business_exemption.is_some() && !ctx.order.tax_total.is_zero() {
+            return Err(PdfError::Validation {
+                field: "tax_total".to_string(),
+                reason: "must be zero with the small-business exemption".to_string(),
+            });
+        }
+    }
+    let (mut operations, header_y) = template.draw_header(doc, ctx)?;
+
+    // Get the y position after drawing addresses
+    let (address_ops, line_y) = template.draw_addresses(ctx, header_y);
+    operations.extend(address_ops);
+
+    // Start body 40 points below the line
+    let mut flow = PageFlow::new(operations, line_y - 40, ctx.properties, ctx.translation);
+    template.draw_body(doc, ctx, &mut flow)?;
+    let footer_ops = template.draw_footer(doc, ctx)?;
+    flow.ops.extend(footer_ops);
+
+    if ctx.properties.strict_glyphs {
+        if let Some(missing) = ctx.fonts.diagnostics.first(DiagnosticKind::MissingGlyph) {
+            return Err(PdfError::MissingGlyph(missing.message));
+        }
+    }
+
+    // Seller's legal details and the watermark on every page
+    let mut page_ops = vec![];
+    if let Some(seller) = &ctx.properties.seller {
+        let footer = SellerFooter::for_seller(seller, ctx.translation);
+        let (footer_ops, _) = footer.draw(
+            50,
+            PAGE_BOTTOM_MARGIN + footer.height(ctx.properties),
+            ctx.properties,
+            ctx.fonts,
+        );
+        page_ops.extend(footer_ops);
+    }
+    if let Some(watermark) = &ctx.properties.watermark {
+        let graphics_state = watermark_graphics_state(doc, watermark);
+        page_ops.extend(draw_watermark(
+            doc,
+            watermark,
+            &graphics_state,
+            ctx.translation,
+            ctx.fonts,
+        )?);
+    }
+
+    Ok(flow
+        .into_pages()
+        .into_iter()
+        .map(|mut operations| {
+            operations.extend(page_ops.iter().cloned());
+            PdfPage::new(Mm(210.0), Mm(297.0), operations)
+        })
+        .collect())
+}
+
+fn build_document<T: DocumentTemplate + ?Sized>(
+    template: &T,
+    order: &Order,
+    order_items: &[OrderLineItem],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+) -> Result<(PdfDocument, Diagnostics), PdfError> {
+    let pdf_properties = properties.input_or_default();
+    let mut doc = PdfDocument::new(template.title());
+    let fonts = load_fonts(
+        &mut doc,
+        Some(translation.language),
+        pdf_properties.font_normal_path.as_deref(),
+        pdf_properties.font_bold_path.as_deref(),
+    )?;
+
+    let ctx = TemplateContext {
+        order,
+        order_items,
+        warehouse_address,
+        properties: &pdf_properties,
+        translation: &translation,
+        fonts: &fonts,
+        logo_data,
+    };
+    let pages = render_pages(&mut doc, template, &ctx)?;
+
+    doc.with_pages(pages);
+    Ok((doc, fonts.diagnostics))
+}
+
+/// Passes writes through, keeping the first error; printpdf ignores write failures.
+struct ErrorTrackingWriter<'a, W: Write> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Write for ErrorTrackingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.error.is_some() {
+            return Err(io::Error::other("previous write failed"));
+        }
+        self.inner.write(buf).inspect_err(|e| {
+            self.error = Some(io::Error::new(e.kind(), e.to_string()));
+        })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Serializes `doc` into `writer`; write failures are returned as [`PdfError::IoError`].
+/// Warnings are recorded in `diagnostics`.
+pub fn save_document<W: Write>(
+    doc: &PdfDocument,
+    writer: &mut W,
+    diagnostics: &Diagnostics,
+) -> Result<(), PdfError> {
+    let mut tracking = ErrorTrackingWriter {
+        inner: writer,
+        error: None,
+    };
+    let mut warnings = Vec::new();
+    doc.save_writer(&mut tracking, &PdfSaveOptions::default(), &mut warnings);
+    diagnostics.extend_printpdf(warnings);
+    if let Some(error) = tracking.error {
+        return Err(PdfError::IoError(error));
+    }
+    tracking.flush()?;
+    Ok(())
+}
+
+pub fn generate_pdf<T: DocumentTemplate + ?Sized>(
+    template: &T,
+    order: &Order,
+    order_items: &[OrderLineItem],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+) -> Result<(Vec<u8>, GenerationReport), PdfError> {
+    let (doc, diagnostics) = build_document(
+        template,
+        order,
+        order_items,
+        warehouse_address,
+        properties,
+        translation,
+        logo_data,
+    )?;
+
+    // Generate the PDF
+    let mut warnings = Vec::new();
+    let bytes = doc.save(&PdfSaveOptions::default(), &mut warnings);
+    diagnostics.extend_printpdf(warnings);
+
+    Ok((bytes, diagnostics.into_report()))
+}
+
+/// Like [`generate_pdf`], but streams the PDF into `writer` instead of returning it.
+#[allow(clippy::too_many_arguments)]
+pub fn write_pdf<T: DocumentTemplate + ?Sized, W: Write>(
+    template: &T,
+    writer: &mut W,
+    order: &Order,
+    order_items: &[OrderLineItem],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+) -> Result<GenerationReport, PdfError> {
+    let (doc, diagnostics) = build_document(
+        template,
+        order,
+        order_items,
+        warehouse_address,
+        properties,
+        translation,
+        logo_data,
+    )?;
+    save_document(&doc, writer, &diagnostics)?;
+    Ok(diagnostics.into_report())
+}
@@ -5,6 +5,29 @@ use std::io;
 pub enum PdfError {
     PrintPdfError(String),
     IoError(io::Error),
+    // Font file could not be read or parsed; `path` is `None` for the embedded fonts
+    FontLoad {
+        path: Option<String>,
+        reason: String,
+    },
+    // PNG or JPEG data could not be decoded
+    ImageDecode(String),
+    // SVG data could not be parsed or rasterized
+    SvgParse(String),
+    // Content of `section` runs `overflow` points past the bottom of the page
+    LayoutOverflow {
+        section: String,
+        overflow: i32,
+    },
+    // Input data is missing or inconsistent
+    Validation {
+        field: String,
+        reason: String,
+    },
+    // A character of the drawn text is not covered by any font, with `strict_glyphs`
+    MissingGlyph(String),
+    // Declarative template could not be parsed or refers to unknown placeholders
+    Template(String),
 }
 
 impl fmt::Display for PdfError {
@@ -12,6 +35,25 @@ impl fmt::Display for PdfError {
         match self {
             PdfError::PrintPdfError(e) => write!(f, "PDF generation error: {}", e),
             PdfError::IoError(e) => write!(f, "I/O error: {}", e),
+            PdfError::FontLoad {
+                path: Some(path),
+                reason,
+            } => write!(f, "Failed to load font {}: {}", path, reason),
+            PdfError::FontLoad { path: None, reason } => {
+                write!(f, "Failed to load embedded font: {}", reason)
+            }
+            PdfError::ImageDecode(e) => write!(f, "Image decoding error: {}", e),
+            PdfError::SvgParse(e) => write!(f, "SVG parsing error: {}", e),
+            PdfError::LayoutOverflow { section, overflow } => write!(
+                f,
+                "Layout overflow: {} runs {}pt past the bottom of the page",
+                section, overflow
+            ),
+            PdfError::Validation { field, reason } => {
+                write!(f, "Invalid {}: {}", field, reason)
+            }
+            PdfError::MissingGlyph(e) => write!(f, "Missing glyph: {}", e),
+            PdfError::Template(e) => write!(f, "Template error: {}", e),
         }
     }
 }
@@ -19,18 +61,12 @@ impl fmt::Display for PdfError {
 impl std::error::Error for PdfError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            PdfError::PrintPdfError(_) => None,
             PdfError::IoError(e) => Some(e),
+            _ => None,
         }
     }
 }
 
-impl From<String> for PdfError {
-    fn from(err: String) -> Self {
-        PdfError::PrintPdfError(err)
-    }
-}
-
 impl From<io::Error> for PdfError {
     fn from(err: io::Error) -> Self {
         PdfError::IoError(err)
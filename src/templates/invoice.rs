@@ -1,17 +1,23 @@
 use crate::types::{
     Address, Dictionary, DocumentProperties, DocumentPropertiesDefault, Order, OrderLineItem,
+    PricingMode,
 };
-use chrono::Datelike;
-use printpdf::{
-    graphics::LinePoint, Color, Mm, Op, PaintMode, PdfDocument, PdfPage, PdfSaveOptions, Point,
-    Polygon, PolygonRing, Rgb, WindingOrder,
-};
+use printpdf::{Op, PdfDocument};
+use std::io::Write;
 
+use super::components::{
+    Align, ColumnLines, Component, ItemsTable, NotesBlock, PaymentBlock, TableColumn,
+    TaxSummaryTable, TextLines, TotalsTable,
+};
+use super::diagnostics::GenerationReport;
+use super::document::{
+    generate_pdf, write_pdf, DocumentTemplate, PageFlow, TemplateContext, HEADER_BOTTOM,
+};
 use super::errors::PdfError;
 use super::pdf_utils::{
-    draw_address, draw_addresses, draw_bold_text, draw_horizontal_line, draw_logo,
-    draw_table_header_background, draw_text, format_decimal, load_fonts, truncate_string,
-    FontBundle,
+    custom_field_lines, draw_address, draw_bold_text, draw_horizontal_line, draw_line, draw_logo,
+    draw_text, format_date, format_decimal, header_fields, order_status_text, supply_date_text,
+    tax_treatment_notice, totals_rows, FontBundle,
 };
 
 fn draw_header(
@@ -22,12 +28,12 @@ fn draw_header(
     warehouse_address: &Address,
     fonts: &FontBundle,
     logo_data: Option<&[u8]>,
-) -> Result<Vec<Op>, PdfError> {
+) -> Result<(Vec<Op>, i32), PdfError> {
     let mut ops = vec![];
 
     // Add logo in top right if provided
     if let Some(logo) = logo_data {
-        ops.extend(draw_logo(doc, 50, 790, Some(logo), 80.0, 24.0)?);
+        ops.extend(draw_logo(doc, 50, 790, Some(logo), 80.0, 24.0, fonts)?);
     }
 
     ops.extend(draw_bold_text(
@@ -51,7 +57,11 @@ fn draw_header(
     ops.extend(draw_text(
         350,
         740,
-        &format!("{}{}", translation.invoice_number_prefix, order.id),
+        &format!(
+            "{}{}",
+            translation.invoice_number_prefix,
+            order.invoice_number()
+        ),
         pdf_properties.font_size_body,
         fonts,
     ));
@@ -61,12 +71,7 @@ fn draw_header(
         &format!(
             "{} {}",
             translation.date_label,
-            format!(
-                "{:04}-{:02}-{:02}",
-                order.created_at.year(),
-                order.created_at.month(),
-                order.created_at.day()
-            )
+            format_date(order.invoice_date())
         ),
         pdf_properties.font_size_body,
         fonts,
@@ -74,252 +79,255 @@ fn draw_header(
     ops.extend(draw_text(
         350,
         700,
-        &format!("{} {}", translation.order_status_label, order.status),
+        &format!(
+            "{} {}",
+            translation.order_status_label,
+            order_status_text(order, translation)
+        ),
         pdf_properties.font_size_body,
         fonts,
     ));
+    let mut current_y = 680;
+    if let Some(supply_date) = supply_date_text(order, translation) {
+        ops.extend(draw_text(
+            350,
+            current_y,
+            &supply_date,
+            pdf_properties.font_size_body,
+            fonts,
+        ));
+        current_y -= 20;
+    }
+
+    // Customer references below the document info
+    let fields = TextLines {
+        lines: header_fields(order, translation),
+        line_height: 20,
+    };
+    let (fields_ops, fields_height) = fields.draw(350, current_y, pdf_properties, fonts);
+    ops.extend(fields_ops);
+    current_y -= fields_height;
 
-    ops.push(draw_horizontal_line(630));
+    // Custom fields, in two columns when there are many
+    let custom_fields = ColumnLines {
+        lines: custom_field_lines(order),
+        line_height: 20,
+        max_rows: 3,
+        column_width: 100,
+    };
+    let (custom_ops, custom_height) = custom_fields.draw(350, current_y, pdf_properties, fonts);
+    ops.extend(custom_ops);
 
-    Ok(ops)
+    let line_y = (current_y - custom_height - 10).min(HEADER_BOTTOM);
+    ops.push(draw_horizontal_line(line_y));
+
+    Ok((ops, line_y))
 }
 
-fn draw_items_at(
+fn draw_items(
     pdf_properties: &DocumentPropertiesDefault,
     translation: &Dictionary,
     order: &Order,
     items: &[OrderLineItem],
-    start_y: i32,
+    flow: &mut PageFlow,
     fonts: &FontBundle,
-) -> Vec<Op> {
-    let mut ops = vec![];
-    let mut current_y = start_y;
-
+) -> Result<(), PdfError> {
     const PRODUCT_DESC_X: i32 = 50;
-    const PRODUCT_DESC_WIDTH: i32 = 180;
-    const QUANTITY_X: i32 = PRODUCT_DESC_X + PRODUCT_DESC_WIDTH;
-    const UNIT_PRICE_X: i32 = QUANTITY_X + 50;
-    const DISCOUNT_X: i32 = UNIT_PRICE_X + 70;
-    const TAX_X: i32 = DISCOUNT_X + 70;
-    const TOTAL_X: i32 = TAX_X + 70;
-
-    // Draw table header background
-    ops.extend(draw_table_header_background(pdf_properties, current_y));
 
-    ops.extend(draw_bold_text(
-        PRODUCT_DESC_X,
-        current_y + 5,
-        &translation.product_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        QUANTITY_X,
-        current_y + 5,
-        &translation.quantity_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        UNIT_PRICE_X - 0,
-        current_y + 5,
-        &translation.unit_price_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        DISCOUNT_X - 0,
-        current_y + 5,
-        &translation.discount_label,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        TAX_X - 0,
-        current_y + 5,
-        &translation.tax_label,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        TOTAL_X - 0,
-        current_y + 5,
+    let show_tax = pdf_properties.small_business_exemption.is_none();
+    // Small-business exemption: no tax column
+    let mut columns = vec![
+        TableColumn::new(&translation.product_header, 0, 180, Align::Left).with_max_chars(25),
+        TableColumn::new(&translation.quantity_header, 180, 35, Align::Right),
+        TableColumn::new(&translation.unit_price_header, 230, 45, Align::Right),
+        TableColumn::new(&translation.discount_label, 300, 45, Align::Right),
+    ];
+    if show_tax {
+        columns.push(TableColumn::new(
+            &translation.tax_label,
+            370,
+            45,
+            Align::Right,
+        ));
+    }
+    columns.push(TableColumn::new(
         &translation.total_label,
-        pdf_properties.font_size_label,
-        fonts,
+        440,
+        45,
+        Align::Right,
     ));
+    let table = ItemsTable {
+        columns,
+        rows: items
+            .iter()
+            .map(|item| {
+                // Format product title and SKU
+                let desc = match &item.sku {
+                    Some(sku) if !sku.is_empty() => format!("{} [{}]", item.title, sku),
+                    _ => item.title.clone(),
+                };
+                let (unit_price, discount_total) = match order.pricing_mode {
+                    PricingMode::Net => (item.unit_price, item.discount_total),
+                    PricingMode::Gross => (item.gross_unit_price(), item.gross_discount_total()),
+                };
+                let mut row = vec![
+                    desc.into(),
+                    item.quantity.to_string().into(),
+                    format_decimal(unit_price, &order.currency).into(),
+                    format_decimal(discount_total, &order.currency).into(),
+                ];
+                if show_tax {
+                    row.push(format_decimal(item.tax_total, &order.currency).into());
+                }
+                row.push(format_decimal(item.total, &order.currency).into());
+                row
+            })
+            .collect(),
+    };
+    table.draw_flow(PRODUCT_DESC_X, 0, flow, pdf_properties, fonts)?;
 
-    current_y -= 25;
-
-    // Draw items
-    for item in items {
-        // Format product title and SKU
-        let desc = match &item.sku {
-            Some(sku) if !sku.is_empty() => format!("{} [{}]", item.title, sku),
-            _ => item.title.clone(),
-        };
-        ops.extend(draw_text(
-            PRODUCT_DESC_X,
-            current_y,
-            &truncate_string(&desc, 25),
-            pdf_properties.font_size_body,
-            fonts,
-        ));
+    let totals = TotalsTable {
+        rows: totals_rows(
+            order,
+            items,
+            translation,
+            &translation.total_label,
+            show_tax,
+        ),
+        width: 215,
+    };
+    // Tax breakdown by rate, left of the totals
+    let tax_summary = show_tax.then(|| TaxSummaryTable::for_order(order, items, translation, 290));
+    flow.place("totals", 20, |y| {
+        let (mut ops, totals_height) = totals.draw(350, y, pdf_properties, fonts);
+        let mut tax_summary_height = 0;
+        if let Some(tax_summary) = &tax_summary {
+            let (tax_summary_ops, height) =
+                tax_summary.draw(PRODUCT_DESC_X, y, pdf_properties, fonts);
+            ops.extend(tax_summary_ops);
+            tax_summary_height = height;
+        }
+        Ok((ops, totals_height.max(tax_summary_height)))
+    })?;
 
-        let quantity_text = item.quantity.to_string();
-        let unit_price_text = format_decimal(item.unit_price, &order.currency);
-        let discount_text = format_decimal(item.discount_total, &order.currency);
-        let tax_text = format_decimal(item.tax_total, &order.currency);
-        let total_text = format_decimal(item.total, &order.currency);
+    if let Some(notice) = tax_treatment_notice(order, pdf_properties, translation) {
+        flow.place("tax_treatment_notice", 40, |y| {
+            let ops = draw_bold_text(
+                PRODUCT_DESC_X,
+                y,
+                notice,
+                pdf_properties.font_size_body,
+                fonts,
+            );
+            Ok((ops, 0))
+        })?;
+    }
 
-        ops.extend(draw_text(
-            QUANTITY_X + 35 - quantity_text.len() as i32 * 6,
-            current_y,
-            &quantity_text,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
-        ops.extend(draw_text(
-            UNIT_PRICE_X + 45 - unit_price_text.len() as i32 * 6,
-            current_y,
-            &unit_price_text,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
-        ops.extend(draw_text(
-            DISCOUNT_X + 45 - discount_text.len() as i32 * 6,
-            current_y,
-            &discount_text,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
-        ops.extend(draw_text(
-            TAX_X + 45 - tax_text.len() as i32 * 6,
-            current_y,
-            &tax_text,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
-        ops.extend(draw_text(
-            TOTAL_X + 45 - total_text.len() as i32 * 6,
-            current_y,
-            &total_text,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
+    if let Some(payment) = &order.payment {
+        let payment = PaymentBlock::for_payment(payment, translation);
+        flow.place("payment", 40, |y| {
+            Ok(payment.draw(PRODUCT_DESC_X, y, pdf_properties, fonts))
+        })?;
+    }
 
-        current_y -= 20;
+    if let Some(notes) = &order.notes {
+        let notes = NotesBlock {
+            title: translation.notes_label.clone(),
+            text: notes.clone(),
+            max_chars: 90,
+        };
+        flow.place("notes", 40, |y| {
+            Ok(notes.draw(PRODUCT_DESC_X, y, pdf_properties, fonts))
+        })?;
     }
 
-    current_y -= 20;
-    let totals = vec![
-        (
-            &translation.subtotal_before_discount_label,
-            order.subtotal_before_discount,
-        ),
-        (&translation.discount_label, order.discount_total),
-        (&translation.subtotal_label, order.subtotal),
-        (&translation.shipping_label, order.shipping_total),
-        (&translation.tax_label, order.tax_total),
-        (&translation.total_label, order.total),
-    ];
+    Ok(())
+}
 
-    for (label, amount) in totals {
-        current_y -= 20;
-        ops.push(Op::SetFillColor {
-            col: Color::Rgb(Rgb {
-                r: 0.95,
-                g: 0.95,
-                b: 0.95,
-                icc_profile: None,
-            }),
-        });
-        ops.push(Op::DrawPolygon {
-            polygon: Polygon {
-                rings: vec![PolygonRing {
-                    points: vec![
-                        LinePoint {
-                            p: Point::new(
-                                Mm((TAX_X - 70) as f32 * 0.352778),
-                                Mm(current_y as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                        LinePoint {
-                            p: Point::new(
-                                Mm((TAX_X - 70 + 215) as f32 * 0.352778),
-                                Mm(current_y as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                        LinePoint {
-                            p: Point::new(
-                                Mm((TAX_X - 70 + 215) as f32 * 0.352778),
-                                Mm((current_y + 15) as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                        LinePoint {
-                            p: Point::new(
-                                Mm((TAX_X - 70) as f32 * 0.352778),
-                                Mm((current_y + 15) as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                    ],
-                }],
-                mode: PaintMode::Fill,
-                winding_order: WindingOrder::NonZero,
-            },
-        });
+fn draw_authorized_by(
+    doc: &mut PdfDocument,
+    pdf_properties: &DocumentPropertiesDefault,
+    translation: &Dictionary,
+    flow: &mut PageFlow,
+    fonts: &FontBundle,
+) -> Result<(), PdfError> {
+    if pdf_properties.signature_image.is_none() && pdf_properties.seal_image.is_none() {
+        return Ok(());
+    }
 
-        // Reset text color to black for subsequent text
-        ops.push(Op::SetFillColor {
-            col: Color::Rgb(Rgb {
-                r: 0.0,
-                g: 0.0,
-                b: 0.0,
-                icc_profile: None,
-            }),
-        });
-        ops.extend(draw_bold_text(
-            TAX_X - 65,
-            current_y + 2,
-            label,
-            pdf_properties.font_size_body,
+    flow.place("authorized_by", 40, |y| {
+        let mut ops = draw_bold_text(
+            50,
+            y,
+            &translation.authorized_by_label,
+            pdf_properties.font_size_label,
             fonts,
-        ));
+        );
 
-        let amount_text = format_decimal(amount, &order.currency);
-        ops.extend(draw_text(
-            TOTAL_X + 45 - amount_text.len() as i32 * 6,
-            current_y + 2,
-            &amount_text,
-            pdf_properties.font_size_body,
+        // Signature sits on the line, the seal to the right of it
+        let line_y = y - 70;
+        ops.push(draw_line(50, 230, line_y));
+        ops.extend(draw_logo(
+            doc,
+            260,
+            line_y,
+            pdf_properties.seal_image.as_deref(),
+            30.0,
+            30.0,
             fonts,
-        ));
+        )?);
+        ops.extend(draw_logo(
+            doc,
+            50,
+            line_y + 5,
+            pdf_properties.signature_image.as_deref(),
+            50.0,
+            20.0,
+            fonts,
+        )?);
+        Ok((ops, 70))
+    })
+}
+
+pub struct Invoice;
+
+impl DocumentTemplate for Invoice {
+    fn title(&self) -> &str {
+        "Invoice"
     }
 
-    if let Some(notes) = &order.notes {
-        current_y -= 40;
-        ops.extend(draw_text(
-            PRODUCT_DESC_X,
-            current_y,
-            &translation.notes_label,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
-        current_y -= 15;
-        ops.extend(draw_text(
-            PRODUCT_DESC_X,
-            current_y,
-            notes,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
+    fn draw_header(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+    ) -> Result<(Vec<Op>, i32), PdfError> {
+        draw_header(
+            doc,
+            ctx.properties,
+            ctx.translation,
+            ctx.order,
+            ctx.warehouse_address,
+            ctx.fonts,
+            ctx.logo_data,
+        )
     }
 
-    ops
+    fn draw_body(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+        flow: &mut PageFlow,
+    ) -> Result<(), PdfError> {
+        draw_items(
+            ctx.properties,
+            ctx.translation,
+            ctx.order,
+            ctx.order_items,
+            flow,
+            ctx.fonts,
+        )?;
+        draw_authorized_by(doc, ctx.properties, ctx.translation, flow, ctx.fonts)
+    }
 }
 
 pub fn generate_pdf_invoice(
@@ -329,58 +337,35 @@ pub fn generate_pdf_invoice(
     properties: DocumentProperties,
     translation: Dictionary,
     logo_data: Option<&[u8]>,
-) -> Result<Vec<u8>, PdfError> {
-    let pdf_properties = properties.input_or_default();
-    let mut doc = PdfDocument::new("Invoice");
-    let fonts = load_fonts(
-        &mut doc,
-        Some(translation.language),
-        pdf_properties.font_normal_path.as_deref(),
-        pdf_properties.font_bold_path.as_deref(),
-    )?;
-
-    // Create content with all operations
-    let mut operations = Vec::new();
-    operations.extend(draw_header(
-        &mut doc,
-        &pdf_properties,
-        &translation,
+) -> Result<(Vec<u8>, GenerationReport), PdfError> {
+    generate_pdf(
+        &Invoice,
         order,
+        order_items,
         warehouse_address,
-        &fonts,
+        properties,
+        translation,
         logo_data,
-    )?);
-
-    // Get the y position after drawing addresses
-    let (address_ops, line_y) = draw_addresses(
-        &pdf_properties,
-        &translation,
-        &order.shipping_address,
-        &order.billing_address,
-        &translation.ship_to_label,
-        &translation.bill_to_label,
-        &fonts,
-    );
-    operations.extend(address_ops);
+    )
+}
 
-    // Start items section 40 points below the line
-    let items_y = line_y - 40;
-    operations.extend(draw_items_at(
-        &pdf_properties,
-        &translation,
+pub fn write_pdf_invoice<W: Write>(
+    writer: &mut W,
+    order: &Order,
+    order_items: &[OrderLineItem],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+) -> Result<GenerationReport, PdfError> {
+    write_pdf(
+        &Invoice,
+        writer,
         order,
         order_items,
-        items_y,
-        &fonts,
-    ));
-
-    // Create the page with all operations
-    let page = PdfPage::new(Mm(210.0), Mm(297.0), operations);
-
-    // Generate the PDF
-    let bytes = doc
-        .with_pages(vec![page])
-        .save(&PdfSaveOptions::default(), &mut Vec::new());
-
-    Ok(bytes)
+        warehouse_address,
+        properties,
+        translation,
+        logo_data,
+    )
 }
@@ -1,3 +1,8 @@
+pub mod batch;
+pub mod components;
+pub mod declarative;
+pub mod diagnostics;
+pub mod document;
 pub mod errors;
 pub mod invoice;
 pub mod packing_list;
@@ -1,16 +1,20 @@
 use crate::types::{
     Address, Dictionary, DocumentProperties, DocumentPropertiesDefault, Order, OrderLineItem,
 };
-use chrono::Datelike;
-use printpdf::{
-    graphics::LinePoint, Color, Mm, Op, PaintMode, PdfDocument, PdfPage, PdfSaveOptions, Point,
-    Polygon, PolygonRing, Rgb, WindingOrder,
-};
+use printpdf::{Op, PdfDocument};
+use std::io::Write;
 
+use super::components::{
+    Align, ColumnLines, Component, ItemsTable, TableCell, TableColumn, TextLines,
+};
+use super::diagnostics::GenerationReport;
+use super::document::{
+    generate_pdf, write_pdf, DocumentTemplate, PageFlow, TemplateContext, HEADER_BOTTOM,
+};
 use super::errors::PdfError;
 use super::pdf_utils::{
-    draw_address, draw_addresses, draw_bold_text, draw_horizontal_line, draw_logo,
-    draw_table_header_background, draw_text, load_fonts, truncate_string, FontBundle,
+    custom_field_lines, draw_address, draw_addresses, draw_bold_text, draw_horizontal_line,
+    draw_logo, draw_text, format_date, header_fields, order_status_text, FontBundle,
 };
 
 fn draw_header(
@@ -21,12 +25,12 @@ fn draw_header(
     warehouse_address: &Address,
     fonts: &FontBundle,
     logo_data: Option<&[u8]>,
-) -> Result<Vec<Op>, PdfError> {
+) -> Result<(Vec<Op>, i32), PdfError> {
     let mut ops = vec![];
 
     // Add logo in top right if provided
     if let Some(logo) = logo_data {
-        ops.extend(draw_logo(doc, 460, 780, Some(logo), 80.0, 24.0)?);
+        ops.extend(draw_logo(doc, 460, 780, Some(logo), 80.0, 24.0, fonts)?);
     }
 
     ops.extend(draw_bold_text(
@@ -60,12 +64,7 @@ fn draw_header(
         &format!(
             "{} {}",
             translation.date_label,
-            format!(
-                "{:04}-{:02}-{:02}",
-                order.created_at.year(),
-                order.created_at.month(),
-                order.created_at.day()
-            )
+            format_date(order.created_at.date())
         ),
         pdf_properties.font_size_body,
         fonts,
@@ -83,222 +82,232 @@ fn draw_header(
     ops.extend(draw_text(
         350,
         680,
-        &format!("{} {}", translation.order_status_label, order.status),
+        &format!(
+            "{} {}",
+            translation.order_status_label,
+            order_status_text(order, translation)
+        ),
         pdf_properties.font_size_body,
         fonts,
     ));
+    let mut current_y = 660;
 
-    ops.push(draw_horizontal_line(630));
+    // Customer references below the document info
+    let fields = TextLines {
+        lines: header_fields(order, translation),
+        line_height: 20,
+    };
+    let (fields_ops, fields_height) = fields.draw(350, current_y, pdf_properties, fonts);
+    ops.extend(fields_ops);
+    current_y -= fields_height;
 
-    Ok(ops)
+    // Custom fields, in two columns when there are many
+    let custom_fields = ColumnLines {
+        lines: custom_field_lines(order),
+        line_height: 20,
+        max_rows: 3,
+        column_width: 100,
+    };
+    let (custom_ops, custom_height) = custom_fields.draw(350, current_y, pdf_properties, fonts);
+    ops.extend(custom_ops);
+
+    let line_y = (current_y - custom_height - 10).min(HEADER_BOTTOM);
+    ops.push(draw_horizontal_line(line_y));
+
+    Ok((ops, line_y))
 }
 
-fn draw_items_at(
+fn draw_items(
+    doc: &mut PdfDocument,
     pdf_properties: &DocumentPropertiesDefault,
     translation: &Dictionary,
     items: &[OrderLineItem],
-    start_y: i32,
+    flow: &mut PageFlow,
     fonts: &FontBundle,
-) -> Vec<Op> {
-    let mut ops = vec![];
-    let mut current_y = start_y;
-
+) -> Result<(), PdfError> {
     const PRODUCT_DESC_X: i32 = 50;
-    const PRODUCT_DESC_WIDTH: i32 = 250;
-    const SKU_X: i32 = PRODUCT_DESC_X + PRODUCT_DESC_WIDTH;
-    const SKU_WIDTH: i32 = 100;
-    const QUANTITY_X: i32 = SKU_X + SKU_WIDTH;
-    const WEIGHT_X: i32 = QUANTITY_X + 80;
 
-    // Draw table header background
-    ops.extend(draw_table_header_background(pdf_properties, current_y));
+    let table = ItemsTable {
+        columns: vec![
+            TableColumn::new(&translation.product_header, 0, 250, Align::Left).with_max_chars(35),
+            TableColumn::new(&translation.sku_header, 250, 100, Align::Left).with_max_chars(12),
+            TableColumn::new(&translation.quantity_header, 350, 35, Align::Right),
+            TableColumn::new(&translation.packed_header, 430, 10, Align::Left),
+        ],
+        rows: items
+            .iter()
+            .map(|item| {
+                vec![
+                    item.title.as_str().into(),
+                    item.sku.as_deref().unwrap_or("N/A").into(),
+                    item.quantity.to_string().into(),
+                    // Add checkbox for "packed" status
+                    TableCell::Checkbox,
+                ]
+            })
+            .collect(),
+    };
+    table.draw_flow(PRODUCT_DESC_X, 0, flow, pdf_properties, fonts)?;
 
-    ops.extend(draw_bold_text(
-        PRODUCT_DESC_X,
-        current_y + 5,
-        &translation.product_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        SKU_X,
-        current_y + 5,
-        &translation.sku_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        QUANTITY_X,
-        current_y + 5,
-        &translation.quantity_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    ops.extend(draw_bold_text(
-        WEIGHT_X,
-        current_y + 5,
-        &translation.packed_header,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
+    // Package info section
+    flow.place("package_info", 30, |y| {
+        let mut ops = draw_bold_text(
+            50,
+            y,
+            &translation.package_info_title,
+            pdf_properties.font_size_label,
+            fonts,
+        );
+        let mut current_y = y - 20;
 
-    current_y -= 25;
+        let package_fields = vec![
+            format!("{} ___________", translation.package_weight_label),
+            format!(
+                "{} L:_____ W:_____ H:_____",
+                translation.package_dimensions_label
+            ),
+            format!("{} ___________", translation.carrier_label),
+            format!("{} ___________", translation.tracking_number_label),
+        ];
 
-    // Draw items
-    for item in items {
-        ops.extend(draw_text(
-            PRODUCT_DESC_X,
-            current_y,
-            &truncate_string(&item.title, 35),
-            pdf_properties.font_size_body,
+        for field in package_fields {
+            ops.extend(draw_text(
+                50,
+                current_y,
+                &field,
+                pdf_properties.font_size_body,
+                fonts,
+            ));
+            current_y -= 18;
+        }
+        // Down to the last field
+        Ok((ops, y - current_y - 18))
+    })?;
+
+    // Total items summary
+    let total_items: i64 = items.iter().map(|item| item.quantity).sum();
+    flow.place("total_items", 38, |y| {
+        let ops = draw_bold_text(
+            50,
+            y,
+            &format!("{} {}", translation.total_items_label, total_items),
+            pdf_properties.font_size_label,
             fonts,
-        ));
+        );
+        Ok((ops, 0))
+    })?;
 
-        let sku_text = item.sku.as_deref().unwrap_or("N/A");
+    // Packer signature section
+    flow.place("packer_verification", 40, |y| {
+        let mut ops = draw_bold_text(
+            50,
+            y,
+            &translation.packer_verification_title,
+            pdf_properties.font_size_label,
+            fonts,
+        );
         ops.extend(draw_text(
-            SKU_X,
-            current_y,
-            &truncate_string(sku_text, 12),
+            50,
+            y - 20,
+            &format!(
+                "{} ___________________ Date: _________ Time: _________",
+                translation.packed_by_label
+            ),
             pdf_properties.font_size_body,
             fonts,
         ));
-
-        let quantity_text = item.quantity.to_string();
+        let signature_y = y - 40;
         ops.extend(draw_text(
-            QUANTITY_X + 35 - quantity_text.len() as i32 * 6,
-            current_y,
-            &quantity_text,
+            50,
+            signature_y,
+            &format!(
+                "{} ___________________________________",
+                translation.signature_label
+            ),
             pdf_properties.font_size_body,
             fonts,
         ));
 
-        // Add checkbox for "packed" status
-        ops.push(Op::SetOutlineColor {
-            col: Color::Rgb(Rgb {
-                r: 0.0,
-                g: 0.0,
-                b: 0.0,
-                icc_profile: None,
-            }),
-        });
-        ops.push(Op::DrawPolygon {
-            polygon: Polygon {
-                rings: vec![PolygonRing {
-                    points: vec![
-                        LinePoint {
-                            p: Point::new(
-                                Mm(WEIGHT_X as f32 * 0.352778),
-                                Mm(current_y as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                        LinePoint {
-                            p: Point::new(
-                                Mm((WEIGHT_X + 10) as f32 * 0.352778),
-                                Mm(current_y as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                        LinePoint {
-                            p: Point::new(
-                                Mm((WEIGHT_X + 10) as f32 * 0.352778),
-                                Mm((current_y + 10) as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                        LinePoint {
-                            p: Point::new(
-                                Mm(WEIGHT_X as f32 * 0.352778),
-                                Mm((current_y + 10) as f32 * 0.352778),
-                            ),
-                            bezier: false,
-                        },
-                    ],
-                }],
-                mode: PaintMode::Stroke,
-                winding_order: WindingOrder::NonZero,
-            },
-        });
+        // Signature over the signature line, seal to the right of it
+        ops.extend(draw_logo(
+            doc,
+            120,
+            signature_y - 5,
+            pdf_properties.signature_image.as_deref(),
+            50.0,
+            20.0,
+            fonts,
+        )?);
+        ops.extend(draw_logo(
+            doc,
+            400,
+            signature_y - 20,
+            pdf_properties.seal_image.as_deref(),
+            30.0,
+            30.0,
+            fonts,
+        )?);
 
-        current_y -= 20;
-    }
+        // The seal reaches furthest down
+        Ok((ops, 60))
+    })
+}
 
-    // Package info section
-    current_y -= 30;
-    ops.extend(draw_bold_text(
-        50,
-        current_y,
-        &translation.package_info_title,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    current_y -= 20;
+pub struct PackingList;
 
-    let package_fields = vec![
-        format!("{} ___________", translation.package_weight_label),
-        format!(
-            "{} L:_____ W:_____ H:_____",
-            translation.package_dimensions_label
-        ),
-        format!("{} ___________", translation.carrier_label),
-        format!("{} ___________", translation.tracking_number_label),
-    ];
+impl DocumentTemplate for PackingList {
+    fn title(&self) -> &str {
+        "Packing List"
+    }
 
-    for field in package_fields {
-        ops.extend(draw_text(
-            50,
-            current_y,
-            &field,
-            pdf_properties.font_size_body,
-            fonts,
-        ));
-        current_y -= 18;
+    fn has_amounts(&self) -> bool {
+        false
     }
 
-    // Total items summary
-    current_y -= 20;
-    let total_items: i64 = items.iter().map(|item| item.quantity).sum();
-    ops.extend(draw_bold_text(
-        50,
-        current_y,
-        &format!("{} {}", translation.total_items_label, total_items),
-        pdf_properties.font_size_label,
-        fonts,
-    ));
+    fn draw_header(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+    ) -> Result<(Vec<Op>, i32), PdfError> {
+        draw_header(
+            doc,
+            ctx.properties,
+            ctx.translation,
+            ctx.order,
+            ctx.warehouse_address,
+            ctx.fonts,
+            ctx.logo_data,
+        )
+    }
 
-    // Packer signature section
-    current_y -= 40;
-    ops.extend(draw_bold_text(
-        50,
-        current_y,
-        &translation.packer_verification_title,
-        pdf_properties.font_size_label,
-        fonts,
-    ));
-    current_y -= 20;
-    ops.extend(draw_text(
-        50,
-        current_y,
-        &format!(
-            "{} ___________________ Date: _________ Time: _________",
-            translation.packed_by_label
-        ),
-        pdf_properties.font_size_body,
-        fonts,
-    ));
-    current_y -= 20;
-    ops.extend(draw_text(
-        50,
-        current_y,
-        &format!(
-            "{} ___________________________________",
-            translation.signature_label
-        ),
-        pdf_properties.font_size_body,
-        fonts,
-    ));
+    fn draw_addresses(&self, ctx: &TemplateContext, header_y: i32) -> (Vec<Op>, i32) {
+        draw_addresses(
+            ctx.properties,
+            ctx.translation,
+            &ctx.order.shipping_address,
+            &ctx.order.billing_address,
+            &ctx.translation.ship_to_label,
+            &ctx.translation.return_address_label,
+            header_y - 20,
+            ctx.fonts,
+        )
+    }
 
-    ops
+    fn draw_body(
+        &self,
+        doc: &mut PdfDocument,
+        ctx: &TemplateContext,
+        flow: &mut PageFlow,
+    ) -> Result<(), PdfError> {
+        draw_items(
+            doc,
+            ctx.properties,
+            ctx.translation,
+            ctx.order_items,
+            flow,
+            ctx.fonts,
+        )
+    }
 }
 
 pub fn generate_pdf_packing_list(
@@ -308,51 +317,35 @@ pub fn generate_pdf_packing_list(
     properties: DocumentProperties,
     translation: Dictionary,
     logo_data: Option<&[u8]>,
-) -> Result<Vec<u8>, PdfError> {
-    let pdf_properties = properties.input_or_default();
-    let mut doc = PdfDocument::new("Packing List");
-    let fonts = load_fonts(
-        &mut doc,
-        Some(translation.language),
-        pdf_properties.font_normal_path.as_deref(),
-        pdf_properties.font_bold_path.as_deref(),
-    )?;
-
-    let mut operations = Vec::new();
-    operations.extend(draw_header(
-        &mut doc,
-        &pdf_properties,
-        &translation,
+) -> Result<(Vec<u8>, GenerationReport), PdfError> {
+    generate_pdf(
+        &PackingList,
         order,
+        order_items,
         warehouse_address,
-        &fonts,
+        properties,
+        translation,
         logo_data,
-    )?);
-
-    let (address_ops, line_y) = draw_addresses(
-        &pdf_properties,
-        &translation,
-        &order.shipping_address,
-        &order.billing_address,
-        &translation.ship_to_label,
-        &translation.return_address_label,
-        &fonts,
-    );
-    operations.extend(address_ops);
+    )
+}
 
-    let items_y = line_y - 40;
-    operations.extend(draw_items_at(
-        &pdf_properties,
-        &translation,
+pub fn write_pdf_packing_list<W: Write>(
+    writer: &mut W,
+    order: &Order,
+    order_items: &[OrderLineItem],
+    warehouse_address: &Address,
+    properties: DocumentProperties,
+    translation: Dictionary,
+    logo_data: Option<&[u8]>,
+) -> Result<GenerationReport, PdfError> {
+    write_pdf(
+        &PackingList,
+        writer,
+        order,
         order_items,
-        items_y,
-        &fonts,
-    ));
-
-    let page = PdfPage::new(Mm(210.0), Mm(297.0), operations);
-    let bytes = doc
-        .with_pages(vec![page])
-        .save(&PdfSaveOptions::default(), &mut Vec::new());
-
-    Ok(bytes)
+        warehouse_address,
+        properties,
+        translation,
+        logo_data,
+    )
 }
@@ -1,21 +1,153 @@
-use crate::types::{Address, Dictionary, DocumentPropertiesDefault, Language};
+use crate::types::{
+    Address, Dictionary, DocumentPropertiesDefault, Language, Order, OrderLineItem, OrderStatus,
+    PricingMode, SmallBusinessScheme, SupplyDate, TaxKind, TaxLine, TaxTreatment, Watermark,
+    WatermarkContent,
+};
 use printpdf::{
     graphics::{Line, LinePoint},
-    Color, FontId, Mm, Op, PaintMode, ParsedFont, PdfDocument, Point, Polygon, PolygonRing, Pt,
-    RawImage, Rgb, TextItem, WindingOrder, XObjectTransform,
+    Color, ExtendedGraphicsState, ExtendedGraphicsStateId, FontId, Mm, Op, PaintMode, ParsedFont,
+    PdfDocument, Point, Polygon, PolygonRing, Pt, Px, RawImage, Rgb, TextItem, TextMatrix,
+    WindingOrder, XObjectId, XObjectRotation, XObjectTransform,
 };
 use resvg::{tiny_skia, usvg};
 use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
 use unicode_script::{Script, UnicodeScript};
 use usvg::TreeParsing;
 
+use super::diagnostics::{DiagnosticKind, Diagnostics, Severity};
 use super::errors::PdfError;
 
+/// Characters a font has glyphs for.
+#[derive(Clone, Debug, Default)]
+pub struct GlyphCoverage(HashSet<char>);
+
+impl GlyphCoverage {
+    /// Reads the Unicode character map of a TrueType/OpenType font
+    pub fn from_font_bytes(data: &[u8]) -> Self {
+        let mut chars = HashSet::new();
+        if let Ok(face) = ttf_parser::Face::parse(data, 0) {
+            if let Some(cmap) = face.tables().cmap {
+                for subtable in cmap.subtables.into_iter().filter(|s| s.is_unicode()) {
+                    subtable.codepoints(|codepoint| {
+                        if let Some(c) = char::from_u32(codepoint) {
+                            chars.insert(c);
+                        }
+                    });
+                }
+            }
+        }
+        GlyphCoverage(chars)
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        // Whitespace and control characters are not drawn
+        c.is_whitespace() || c.is_control() || self.0.contains(&c)
+    }
+}
+
 pub struct FontBundle {
     pub normal: FontId,
     pub bold: FontId,
     pub normal_fallback: Option<FontId>,
     pub bold_fallback: Option<FontId>,
+    // Glyphs per font; fonts without an entry are assumed to cover everything
+    pub coverage: HashMap<FontId, GlyphCoverage>,
+    // Collects warnings from everything drawn with these fonts
+    pub diagnostics: Diagnostics,
+    // Images registered with the same document as the fonts
+    pub images: ImageCache,
+}
+
+// Hash of the image data, width and height of its box in mm
+type ImageKey = (u64, u32, u32);
+
+/// Images already registered with a document, with their pixel dimensions. Data drawn
+/// again in a box of the same size, e.g. the logo of every document in a batch, is
+/// embedded once and drawn by id.
+#[derive(Debug, Default)]
+pub struct ImageCache(Mutex<HashMap<ImageKey, (XObjectId, usize, usize)>>);
+
+impl FontBundle {
+    pub fn covers(&self, font: &FontId, c: char) -> bool {
+        self.coverage
+            .get(font)
+            .is_none_or(|coverage| coverage.contains(c))
+    }
+
+    /// True if any font of the bundle has a glyph for `c`
+    pub fn covers_any(&self, c: char) -> bool {
+        [
+            Some(&self.normal),
+            Some(&self.bold),
+            self.normal_fallback.as_ref(),
+            self.bold_fallback.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|font| self.covers(font, c))
+    }
+
+    /// Splits `text` into runs per font. Thai goes to `primary`, everything else to
+    /// `fallback` if present; characters the chosen font lacks use the other one.
+    /// Characters no font covers are recorded as `MissingGlyph`.
+    fn font_runs(
+        &self,
+        text: &str,
+        primary: &FontId,
+        fallback: Option<&FontId>,
+    ) -> Vec<(FontId, String)> {
+        let mut runs: Vec<(FontId, String)> = vec![];
+
+        for segment in segment_text_by_script(text) {
+            let (preferred, other) = match (segment.script, fallback) {
+                (Script::Thai, _) | (_, None) => (primary, fallback),
+                (_, Some(fallback)) => {
+                    self.diagnostics.push(
+                        DiagnosticKind::FontFallback,
+                        Severity::Info,
+                        format!("{:?} text drawn with the fallback font", segment.script),
+                    );
+                    (fallback, Some(primary))
+                }
+            };
+
+            // Runs never span script segments
+            let segment_start = runs.len();
+            for c in segment.text.chars() {
+                if !self.covers_any(c) {
+                    self.diagnostics.push(
+                        DiagnosticKind::MissingGlyph,
+                        Severity::Warning,
+                        format!(
+                            "'{}' (U+{:04X}) in \"{}\" is not covered by any font",
+                            c, c as u32, text
+                        ),
+                    );
+                }
+                let font = match other {
+                    Some(other) if !self.covers(preferred, c) && self.covers(other, c) => {
+                        self.diagnostics.push(
+                            DiagnosticKind::FontFallback,
+                            Severity::Info,
+                            format!("'{}' (U+{:04X}) drawn with the other font", c, c as u32),
+                        );
+                        other
+                    }
+                    _ => preferred,
+                };
+                let in_segment = runs.len() > segment_start;
+                match runs.last_mut() {
+                    Some((run_font, run)) if in_segment && run_font == font => run.push(c),
+                    _ => runs.push((font.clone(), c.to_string())),
+                }
+            }
+        }
+
+        runs
+    }
 }
 
 #[derive(Debug)]
@@ -59,11 +191,11 @@ fn segment_text_by_script(text: &str) -> Vec<TextSegment> {
 
 fn svg_to_rgba_bytes(svg_data: &[u8], width: u32, height: u32) -> Result<Vec<u8>, PdfError> {
     let opt = usvg::Options::default();
-    let tree = usvg::Tree::from_data(svg_data, &opt)
-        .map_err(|e| PdfError::PrintPdfError(format!("SVG parsing error: {}", e)))?;
+    let tree =
+        usvg::Tree::from_data(svg_data, &opt).map_err(|e| PdfError::SvgParse(e.to_string()))?;
 
     let mut pixmap = tiny_skia::Pixmap::new(width, height)
-        .ok_or_else(|| PdfError::PrintPdfError("Failed to create pixmap".to_string()))?;
+        .ok_or_else(|| PdfError::SvgParse("Failed to create pixmap".to_string()))?;
 
     resvg::render(
         &tree,
@@ -74,6 +206,79 @@ fn svg_to_rgba_bytes(svg_data: &[u8], width: u32, height: u32) -> Result<Vec<u8>
     Ok(pixmap.data().to_vec())
 }
 
+/// Decodes PNG/JPEG or rasterizes SVG data and registers it with the document, unless
+/// `fonts` has it already. Returns the image id along with its pixel dimensions.
+fn add_image(
+    doc: &mut PdfDocument,
+    data: &[u8],
+    width_mm: f32,
+    height_mm: f32,
+    fonts: &FontBundle,
+) -> Result<(XObjectId, usize, usize), PdfError> {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    let key = (hasher.finish(), width_mm.to_bits(), height_mm.to_bits());
+    // Held while decoding, so documents laid out in parallel don't add the same image twice
+    let mut images = fonts.images.0.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(image) = images.get(&key) {
+        return Ok(image.clone());
+    }
+
+    let diagnostics = &fonts.diagnostics;
+    let mut warnings = Vec::new();
+
+    // Check if it's SVG or PNG/other image format
+    let raw_image = if data.starts_with(b"<?xml") || data.starts_with(b"<svg") {
+        // SVG processing
+        let pixel_width = (width_mm * 8.0) as u32; // ~200 DPI
+        let pixel_height = (height_mm * 8.0) as u32;
+
+        let rgba_data = svg_to_rgba_bytes(data, pixel_width, pixel_height)?;
+
+        // Create image from RGBA data
+        let dynamic_image = image::DynamicImage::ImageRgba8(
+            image::RgbaImage::from_raw(pixel_width, pixel_height, rgba_data).ok_or_else(|| {
+                PdfError::SvgParse("Failed to create image from RGBA data".to_string())
+            })?,
+        );
+
+        // Convert to raw bytes for RawImage
+        let mut png_bytes = Vec::new();
+        dynamic_image
+            .write_to(
+                &mut std::io::Cursor::new(&mut png_bytes),
+                image::ImageFormat::Png,
+            )
+            .map_err(|e| PdfError::SvgParse(format!("Failed to encode image to PNG: {}", e)))?;
+
+        RawImage::decode_from_bytes(&png_bytes, &mut warnings).map_err(PdfError::SvgParse)?
+    } else {
+        // Direct PNG/JPEG/other image format processing
+        let raw_image =
+            RawImage::decode_from_bytes(data, &mut warnings).map_err(PdfError::ImageDecode)?;
+
+        // Raster images are placed at 300 DPI
+        let image_width_mm = raw_image.width as f32 * 25.4 / 300.0;
+        let image_height_mm = raw_image.height as f32 * 25.4 / 300.0;
+        if image_width_mm > width_mm || image_height_mm > height_mm {
+            diagnostics.push(
+                DiagnosticKind::ImageOversized,
+                Severity::Warning,
+                format!(
+                    "Image is {:.0}x{:.0} mm at 300 DPI, larger than its {:.0}x{:.0} mm box",
+                    image_width_mm, image_height_mm, width_mm, height_mm
+                ),
+            );
+        }
+        raw_image
+    };
+    diagnostics.extend_printpdf(warnings);
+
+    let image = (doc.add_image(&raw_image), raw_image.width, raw_image.height);
+    images.insert(key, image.clone());
+    Ok(image)
+}
+
 pub fn draw_logo(
     doc: &mut PdfDocument,
     x: i32,
@@ -81,95 +286,380 @@ pub fn draw_logo(
     image_data: Option<&[u8]>,
     width_mm: f32,
     height_mm: f32,
+    fonts: &FontBundle,
 ) -> Result<Vec<Op>, PdfError> {
     match image_data {
         Some(data) => {
-            // Check if it's SVG or PNG/other image format
-            if data.starts_with(b"<?xml") || data.starts_with(b"<svg") {
-                // SVG processing
-                let pixel_width = (width_mm * 8.0) as u32; // ~200 DPI
-                let pixel_height = (height_mm * 8.0) as u32;
-
-                let rgba_data = svg_to_rgba_bytes(data, pixel_width, pixel_height)?;
-
-                // Create image from RGBA data
-                let dynamic_image = image::DynamicImage::ImageRgba8(
-                    image::RgbaImage::from_raw(pixel_width, pixel_height, rgba_data).ok_or_else(
-                        || {
-                            PdfError::PrintPdfError(
-                                "Failed to create image from RGBA data".to_string(),
-                            )
-                        },
-                    )?,
-                );
-
-                // Convert to raw bytes for RawImage
-                let mut png_bytes = Vec::new();
-                dynamic_image
-                    .write_to(
-                        &mut std::io::Cursor::new(&mut png_bytes),
-                        image::ImageFormat::Png,
-                    )
-                    .map_err(|e| {
-                        PdfError::PrintPdfError(format!("Failed to encode image to PNG: {}", e))
-                    })?;
-
-                // Decode as RawImage and add to document
-                let mut warnings = Vec::new();
-                let raw_image =
-                    RawImage::decode_from_bytes(&png_bytes, &mut warnings).map_err(|e| {
-                        PdfError::PrintPdfError(format!("Failed to decode RawImage: {}", e))
-                    })?;
-
-                let image_xobject_id = doc.add_image(&raw_image);
-
-                // Create transform for positioning and scaling
-                let transform = XObjectTransform {
-                    translate_x: Some(Mm(x as f32 * 0.352778).into()),
-                    translate_y: Some(Mm(y as f32 * 0.352778).into()),
-                    scale_x: Some(1.0), // Scale factor
-                    scale_y: Some(1.0), // Scale factor
-                    ..Default::default()
-                };
+            let (image_xobject_id, _, _) = add_image(doc, data, width_mm, height_mm, fonts)?;
 
-                Ok(vec![Op::UseXobject {
-                    id: image_xobject_id,
-                    transform,
-                }])
-            } else {
-                // Direct PNG/JPEG/other image format processing
-                let mut warnings = Vec::new();
-                let raw_image = RawImage::decode_from_bytes(data, &mut warnings).map_err(|e| {
-                    PdfError::PrintPdfError(format!("Failed to decode RawImage: {}", e))
-                })?;
-
-                let image_xobject_id = doc.add_image(&raw_image);
-
-                // Create transform for positioning and scaling
-                let transform = XObjectTransform {
-                    translate_x: Some(Mm(x as f32 * 0.352778).into()),
-                    translate_y: Some(Mm(y as f32 * 0.352778).into()),
-                    scale_x: Some(1.0), // Scale factor
-                    scale_y: Some(1.0), // Scale factor
-                    ..Default::default()
-                };
+            // Create transform for positioning and scaling
+            let transform = XObjectTransform {
+                translate_x: Some(Mm(x as f32 * 0.352778).into()),
+                translate_y: Some(Mm(y as f32 * 0.352778).into()),
+                scale_x: Some(1.0), // Scale factor
+                scale_y: Some(1.0), // Scale factor
+                ..Default::default()
+            };
 
-                Ok(vec![Op::UseXobject {
-                    id: image_xobject_id,
-                    transform,
-                }])
-            }
+            Ok(vec![Op::UseXobject {
+                id: image_xobject_id,
+                transform,
+            }])
         }
         None => Ok(vec![]),
     }
 }
 
+/// Adds the watermark's transparency to the document; create it once per document and pass
+/// the id to [`draw_watermark`] for every page.
+pub fn watermark_graphics_state(
+    doc: &mut PdfDocument,
+    watermark: &Watermark,
+) -> ExtendedGraphicsStateId {
+    let opacity = watermark.opacity.unwrap_or(0.15).clamp(0.0, 1.0);
+    doc.add_graphics_state(
+        ExtendedGraphicsState::default()
+            .with_current_fill_alpha(opacity)
+            .with_current_stroke_alpha(opacity),
+    )
+}
+
+/// Draws the watermark centered on an A4 page, with the graphics state from
+/// [`watermark_graphics_state`].
+pub fn draw_watermark(
+    doc: &mut PdfDocument,
+    watermark: &Watermark,
+    graphics_state: &ExtendedGraphicsStateId,
+    translation: &Dictionary,
+    fonts: &FontBundle,
+) -> Result<Vec<Op>, PdfError> {
+    const PAGE_CENTER_X: f32 = 595.0 / 2.0;
+    const PAGE_CENTER_Y: f32 = 842.0 / 2.0;
+
+    let rotation = watermark.rotation.unwrap_or(45.0);
+    let (r, g, b) = watermark.color.unwrap_or((0.8, 0.1, 0.1));
+    let font_size = watermark.font_size.unwrap_or(80.0);
+
+    let mut ops = vec![
+        Op::SaveGraphicsState,
+        Op::LoadGraphicsState {
+            gs: graphics_state.clone(),
+        },
+    ];
+
+    let text = match &watermark.content {
+        WatermarkContent::Paid => &translation.watermark_paid,
+        WatermarkContent::Draft => &translation.watermark_draft,
+        WatermarkContent::Copy => &translation.watermark_copy,
+        WatermarkContent::Cancelled => &translation.watermark_cancelled,
+        WatermarkContent::Void => &translation.watermark_void,
+        WatermarkContent::Text(text) => text,
+        WatermarkContent::Image(data) => {
+            let (image_xobject_id, width, height) = add_image(doc, data, 160.0, 160.0, fonts)?;
+
+            // Images are placed at 300 DPI; rotate around the image center
+            let width_pt = width as f32 * 72.0 / 300.0;
+            let height_pt = height as f32 * 72.0 / 300.0;
+            let transform = XObjectTransform {
+                translate_x: Some(Pt(PAGE_CENTER_X - width_pt / 2.0)),
+                translate_y: Some(Pt(PAGE_CENTER_Y - height_pt / 2.0)),
+                rotate: Some(XObjectRotation {
+                    angle_ccw_degrees: rotation,
+                    rotation_center_x: Px(width / 2),
+                    rotation_center_y: Px(height / 2),
+                }),
+                ..Default::default()
+            };
+            ops.push(Op::UseXobject {
+                id: image_xobject_id,
+                transform,
+            });
+            ops.push(Op::RestoreGraphicsState);
+            return Ok(ops);
+        }
+    };
+
+    // Approximate the text width to center the rotated baseline on the page
+    let text_width = text.chars().count() as f32 * font_size * 0.6;
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let start_x = PAGE_CENTER_X - (text_width / 2.0) * cos + (font_size / 3.0) * sin;
+    let start_y = PAGE_CENTER_Y - (text_width / 2.0) * sin - (font_size / 3.0) * cos;
+
+    ops.push(Op::SetFillColor {
+        col: Color::Rgb(Rgb {
+            r,
+            g,
+            b,
+            icc_profile: None,
+        }),
+    });
+    ops.push(Op::StartTextSection);
+    ops.push(Op::SetTextMatrix {
+        matrix: TextMatrix::TranslateRotate(Pt(start_x), Pt(start_y), rotation),
+    });
+    for (font_id, text) in fonts.font_runs(text, &fonts.bold, fonts.bold_fallback.as_ref()) {
+        ops.push(Op::SetFontSize {
+            font: font_id.clone(),
+            size: Pt(font_size),
+        });
+        ops.push(Op::WriteText {
+            font: font_id,
+            items: vec![TextItem::Text(text)],
+        });
+    }
+    ops.push(Op::EndTextSection);
+    ops.push(Op::RestoreGraphicsState);
+
+    Ok(ops)
+}
+
 pub fn format_decimal(amount: Decimal, currency: &str) -> String {
     format!("{} {:.2}", currency, amount)
 }
 
+/// ISO 8601, e.g. "2023-01-31"
+pub fn format_date(date: chrono::NaiveDate) -> String {
+    use chrono::Datelike;
+    format!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day())
+}
+
+/// Localized order status, or the custom status as-is
+pub fn order_status_text<'a>(order: &'a Order, translation: &'a Dictionary) -> &'a str {
+    match &order.status {
+        OrderStatus::Pending => &translation.status_pending,
+        OrderStatus::Paid => &translation.status_paid,
+        OrderStatus::Processing => &translation.status_processing,
+        OrderStatus::Shipped => &translation.status_shipped,
+        OrderStatus::Completed => &translation.status_completed,
+        OrderStatus::Cancelled => &translation.status_cancelled,
+        OrderStatus::Refunded => &translation.status_refunded,
+        OrderStatus::Custom(status) => status,
+    }
+}
+
+/// Customer number, PO number, contact person and cost center lines for the header,
+/// as far as the order has them
+pub fn header_fields(order: &Order, translation: &Dictionary) -> Vec<String> {
+    [
+        (&translation.customer_number_label, &order.customer_number),
+        (
+            &translation.customer_reference_label,
+            &order.customer_reference,
+        ),
+        (&translation.contact_person_label, &order.contact_person),
+        (&translation.cost_center_label, &order.cost_center),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| Some(format!("{} {}", label, value.as_ref()?)))
+    .collect()
+}
+
+/// `Order::custom_fields` as header lines, label and value separated by a space
+pub fn custom_field_lines(order: &Order) -> Vec<String> {
+    order
+        .custom_fields
+        .iter()
+        .map(|(label, value)| format!("{} {}", label, value))
+        .collect()
+}
+
+/// "Delivery Date: 2023-01-31" or "Service Period: 2023-01-01 – 2023-01-31",
+/// if the order has a supply date
+pub fn supply_date_text(order: &Order, translation: &Dictionary) -> Option<String> {
+    let supply_date = order.invoice.as_ref()?.supply_date?;
+    Some(match supply_date {
+        SupplyDate::Date(date) => {
+            format!("{} {}", translation.supply_date_label, format_date(date))
+        }
+        SupplyDate::Period { start, end } => format!(
+            "{} {} – {}",
+            translation.service_period_label,
+            format_date(start),
+            format_date(end)
+        ),
+    })
+}
+
+/// Localized name of a tax with jurisdiction and rate, e.g. "State Tax CA (7.25%)"
+fn tax_line_name(tax: &TaxLine, translation: &Dictionary) -> String {
+    let mut name = match &tax.kind {
+        TaxKind::Vat => translation.tax_vat_label.clone(),
+        TaxKind::Gst => translation.tax_gst_label.clone(),
+        TaxKind::Hst => translation.tax_hst_label.clone(),
+        TaxKind::Pst => translation.tax_pst_label.clone(),
+        TaxKind::Qst => translation.tax_qst_label.clone(),
+        TaxKind::StateSalesTax => translation.tax_state_label.clone(),
+        TaxKind::CountySalesTax => translation.tax_county_label.clone(),
+        TaxKind::CitySalesTax => translation.tax_city_label.clone(),
+        TaxKind::Custom(name) => name.clone(),
+    };
+    if let Some(jurisdiction) = &tax.jurisdiction {
+        name = format!("{} {}", name, jurisdiction);
+    }
+    if let Some(rate) = tax.rate {
+        name = format!("{} ({}%)", name, rate.normalize());
+    }
+    name
+}
+
+/// Totals label for a tax line, e.g. "State Tax CA (7.25%):"
+pub fn tax_line_label(tax: &TaxLine, translation: &Dictionary) -> String {
+    format!("{}:", tax_line_name(tax, translation))
+}
+
+/// Totals rows for the order's tax: one per tax line, or a single "Tax:" row without any
+pub fn tax_rows(order: &Order, translation: &Dictionary) -> Vec<(String, String)> {
+    if order.tax_lines.is_empty() {
+        return vec![(
+            translation.tax_label.clone(),
+            format_decimal(order.tax_total, &order.currency),
+        )];
+    }
+    order
+        .tax_lines
+        .iter()
+        .map(|tax| {
+            (
+                tax_line_label(tax, translation),
+                format_decimal(tax.amount, &order.currency),
+            )
+        })
+        .collect()
+}
+
+/// Tax included in gross prices, e.g. "incl. 19% VAT:": one row per tax line, else per
+/// rate of the tax summary, else a single row. Rates without tax are left out.
+pub fn included_tax_rows(
+    order: &Order,
+    items: &[OrderLineItem],
+    translation: &Dictionary,
+) -> Vec<(String, String)> {
+    let included = |tax: &str, amount: Decimal| {
+        (
+            translation.tax_included_label.replace("{tax}", tax),
+            format_decimal(amount, &order.currency),
+        )
+    };
+
+    if !order.tax_lines.is_empty() {
+        return order
+            .tax_lines
+            .iter()
+            .map(|tax| included(&tax_line_name(tax, translation), tax.amount))
+            .collect();
+    }
+    let summary = order.tax_summary(items);
+    if summary.is_empty() {
+        return vec![included(&translation.tax_header, order.tax_total)];
+    }
+    summary
+        .into_iter()
+        .filter(|row| !row.tax.is_zero())
+        .map(|row| {
+            let tax = format!("{}% {}", row.rate, translation.tax_vat_label);
+            included(&tax, row.tax)
+        })
+        .collect()
+}
+
+/// Legal notice for the order's tax treatment, e.g. reverse charge; none for standard VAT.
+/// The small-business exemption statement of the seller's scheme takes precedence.
+pub fn tax_treatment_notice<'a>(
+    order: &Order,
+    pdf_properties: &'a DocumentPropertiesDefault,
+    translation: &'a Dictionary,
+) -> Option<&'a str> {
+    if let Some(scheme) = &pdf_properties.small_business_exemption {
+        return Some(match scheme {
+            SmallBusinessScheme::Germany => &translation.small_business_notice_germany,
+            SmallBusinessScheme::France => &translation.small_business_notice_france,
+            SmallBusinessScheme::Italy => &translation.small_business_notice_italy,
+            SmallBusinessScheme::Portugal => &translation.small_business_notice_portugal,
+            SmallBusinessScheme::Other => &translation.small_business_notice,
+            SmallBusinessScheme::Custom(notice) => notice,
+        });
+    }
+    match order.tax_treatment {
+        TaxTreatment::Standard => None,
+        TaxTreatment::ReverseCharge => Some(&translation.reverse_charge_notice),
+        TaxTreatment::IntraCommunity => Some(&translation.intra_community_notice),
+        TaxTreatment::Export => Some(&translation.export_notice),
+        TaxTreatment::Exempt => Some(&translation.exempt_notice),
+    }
+}
+
+/// Subtotals, shipping, tax and `total_label` rows of the totals block. Gross pricing
+/// shows amounts including tax and lists the included tax after the total.
+/// Without `show_tax` (small-business exemption), the tax rows are left out.
+pub fn totals_rows(
+    order: &Order,
+    items: &[OrderLineItem],
+    translation: &Dictionary,
+    total_label: &str,
+    show_tax: bool,
+) -> Vec<(String, String)> {
+    let currency = &order.currency;
+    let total = (
+        total_label.to_string(),
+        format_decimal(order.total, currency),
+    );
+
+    match order.pricing_mode {
+        PricingMode::Net => {
+            let mut rows = vec![
+                (
+                    translation.subtotal_before_discount_label.clone(),
+                    format_decimal(order.subtotal_before_discount, currency),
+                ),
+                (
+                    translation.discount_label.clone(),
+                    format_decimal(order.discount_total, currency),
+                ),
+                (
+                    translation.subtotal_label.clone(),
+                    format_decimal(order.subtotal, currency),
+                ),
+                (
+                    translation.shipping_label.clone(),
+                    format_decimal(order.shipping_total, currency),
+                ),
+            ];
+            if show_tax {
+                rows.extend(tax_rows(order, translation));
+            }
+            rows.push(total);
+            rows
+        }
+        PricingMode::Gross => {
+            let gross = order.gross_totals(items);
+            let mut rows = vec![
+                (
+                    translation.subtotal_before_discount_label.clone(),
+                    format_decimal(gross.subtotal_before_discount, currency),
+                ),
+                (
+                    translation.discount_label.clone(),
+                    format_decimal(gross.discount_total, currency),
+                ),
+                (
+                    translation.subtotal_label.clone(),
+                    format_decimal(gross.subtotal, currency),
+                ),
+                (
+                    translation.shipping_label.clone(),
+                    format_decimal(gross.shipping_total, currency),
+                ),
+                total,
+            ];
+            if show_tax {
+                rows.extend(included_tax_rows(order, items, translation));
+            }
+            rows
+        }
+    }
+}
+
 pub fn draw_text(x: i32, y: i32, text: &str, font_size: f32, fonts: &FontBundle) -> Vec<Op> {
-    let segments = segment_text_by_script(text);
     let mut ops = vec![
         Op::StartTextSection,
         Op::SetTextCursor {
@@ -177,21 +667,9 @@ pub fn draw_text(x: i32, y: i32, text: &str, font_size: f32, fonts: &FontBundle)
         },
     ];
 
-    for segment in segments {
-        let (font_id, text_to_write) = match segment.script {
-            Script::Thai => (fonts.normal.clone(), segment.text),
-            Script::Latin | Script::Common | _ => {
-                if fonts.normal_fallback.is_some() {
-                    (
-                        fonts.normal_fallback.as_ref().unwrap().clone(),
-                        segment.text,
-                    )
-                } else {
-                    (fonts.normal.clone(), segment.text)
-                }
-            }
-        };
-
+    for (font_id, text_to_write) in
+        fonts.font_runs(text, &fonts.normal, fonts.normal_fallback.as_ref())
+    {
         ops.push(Op::SetFontSize {
             font: font_id.clone(),
             size: Pt(font_size),
@@ -207,7 +685,6 @@ pub fn draw_text(x: i32, y: i32, text: &str, font_size: f32, fonts: &FontBundle)
 }
 
 pub fn draw_bold_text(x: i32, y: i32, text: &str, font_size: f32, fonts: &FontBundle) -> Vec<Op> {
-    let segments = segment_text_by_script(text);
     let mut ops = vec![
         Op::StartTextSection,
         Op::SetTextCursor {
@@ -215,18 +692,8 @@ pub fn draw_bold_text(x: i32, y: i32, text: &str, font_size: f32, fonts: &FontBu
         },
     ];
 
-    for segment in segments {
-        let (font_id, text_to_write) = match segment.script {
-            Script::Thai => (fonts.bold.clone(), segment.text),
-            Script::Latin | Script::Common | _ => {
-                if fonts.bold_fallback.is_some() {
-                    (fonts.bold_fallback.as_ref().unwrap().clone(), segment.text)
-                } else {
-                    (fonts.bold.clone(), segment.text)
-                }
-            }
-        };
-
+    for (font_id, text_to_write) in fonts.font_runs(text, &fonts.bold, fonts.bold_fallback.as_ref())
+    {
         ops.push(Op::SetFontSize {
             font: font_id.clone(),
             size: Pt(font_size),
@@ -241,20 +708,47 @@ pub fn draw_bold_text(x: i32, y: i32, text: &str, font_size: f32, fonts: &FontBu
     ops
 }
 
+/// `s` cut to `max_len` characters including a trailing "...". Counts characters,
+/// not bytes, so Thai text or umlauts are never split.
 pub fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[0..max_len - 3])
+        let kept: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     }
 }
 
+fn read_font(path: &str) -> Result<Vec<u8>, PdfError> {
+    std::fs::read(path).map_err(|e| PdfError::FontLoad {
+        path: Some(path.to_string()),
+        reason: e.to_string(),
+    })
+}
+
+/// Parses font data; `path` is `None` for the embedded fonts
+fn parse_font(
+    data: &[u8],
+    path: Option<&str>,
+    style: &str,
+    diagnostics: &Diagnostics,
+) -> Result<ParsedFont, PdfError> {
+    let mut warnings = Vec::new();
+    let font = ParsedFont::from_bytes(data, 0, &mut warnings).ok_or_else(|| PdfError::FontLoad {
+        path: path.map(str::to_string),
+        reason: format!("Failed to parse {} font", style),
+    });
+    diagnostics.extend_printpdf(warnings);
+    font
+}
+
 pub fn load_fonts(
     doc: &mut PdfDocument,
     language: Option<Language>,
     custom_font_normal_path: Option<&str>,
     custom_font_bold_path: Option<&str>,
 ) -> Result<FontBundle, PdfError> {
+    let diagnostics = Diagnostics::default();
     match language {
         Some(Language::Thai) => {
             // For Thai language, use NotoSans fonts which have good Unicode coverage
@@ -269,69 +763,95 @@ pub fn load_fonts(
 
             // Use Thai fonts as primary for proper Thai character rendering
             let font_thai_normal =
-                ParsedFont::from_bytes(NOTO_SANS_THAI_REGULAR, 0, &mut Vec::new()).ok_or(
-                    PdfError::PrintPdfError("Failed to load Thai normal font".to_string()),
-                )?;
-            let font_thai_bold = ParsedFont::from_bytes(NOTO_SANS_THAI_BOLD, 0, &mut Vec::new())
-                .ok_or(PdfError::PrintPdfError(
-                    "Failed to load Thai bold font".to_string(),
-                ))?;
+                parse_font(NOTO_SANS_THAI_REGULAR, None, "Thai normal", &diagnostics)?;
+            let font_thai_bold = parse_font(NOTO_SANS_THAI_BOLD, None, "Thai bold", &diagnostics)?;
             // Regular NotoSans as fallback for English/Latin characters
-            let font_normal = ParsedFont::from_bytes(NOTO_SANS_REGULAR, 0, &mut Vec::new()).ok_or(
-                PdfError::PrintPdfError("Failed to load normal font".to_string()),
-            )?;
-            let font_bold = ParsedFont::from_bytes(NOTO_SANS_BOLD, 0, &mut Vec::new()).ok_or(
-                PdfError::PrintPdfError("Failed to load bold font".to_string()),
-            )?;
+            let font_normal = parse_font(NOTO_SANS_REGULAR, None, "normal", &diagnostics)?;
+            let font_bold = parse_font(NOTO_SANS_BOLD, None, "bold", &diagnostics)?;
 
             let font_thai_normal_id = doc.add_font(&font_thai_normal);
             let font_thai_bold_id = doc.add_font(&font_thai_bold);
             let font_normal_id = doc.add_font(&font_normal);
             let font_bold_id = doc.add_font(&font_bold);
 
+            let coverage = HashMap::from([
+                (
+                    font_thai_normal_id.clone(),
+                    GlyphCoverage::from_font_bytes(NOTO_SANS_THAI_REGULAR),
+                ),
+                (
+                    font_thai_bold_id.clone(),
+                    GlyphCoverage::from_font_bytes(NOTO_SANS_THAI_BOLD),
+                ),
+                (
+                    font_normal_id.clone(),
+                    GlyphCoverage::from_font_bytes(NOTO_SANS_REGULAR),
+                ),
+                (
+                    font_bold_id.clone(),
+                    GlyphCoverage::from_font_bytes(NOTO_SANS_BOLD),
+                ),
+            ]);
+
             Ok(FontBundle {
                 normal: font_thai_normal_id,
                 bold: font_thai_bold_id,
                 normal_fallback: Some(font_normal_id),
                 bold_fallback: Some(font_bold_id),
+                coverage,
+                diagnostics,
+                images: ImageCache::default(),
             })
         }
         _ => {
             // Default fonts for all other languages (en, de, fr, es, pt, it)
             // Use custom fonts if provided, otherwise use built-in NotoSans
             let normal_font_data = match custom_font_normal_path {
-                Some(path) => std::fs::read(path).map_err(|e| {
-                    PdfError::PrintPdfError(format!("Failed to read font file {}: {}", path, e))
-                })?,
+                Some(path) => read_font(path)?,
                 None => include_bytes!("../../fonts/NotoSans-Regular.ttf").to_vec(),
             };
             let bold_font_data = match custom_font_bold_path {
-                Some(path) => std::fs::read(path).map_err(|e| {
-                    PdfError::PrintPdfError(format!("Failed to read font file {}: {}", path, e))
-                })?,
+                Some(path) => read_font(path)?,
                 None => include_bytes!("../../fonts/NotoSans-SemiBold.ttf").to_vec(),
             };
 
-            let font_normal = ParsedFont::from_bytes(&normal_font_data, 0, &mut Vec::new()).ok_or(
-                PdfError::PrintPdfError("Failed to load normal font".to_string()),
-            )?;
-            let font_bold = ParsedFont::from_bytes(&bold_font_data, 0, &mut Vec::new()).ok_or(
-                PdfError::PrintPdfError("Failed to load bold font".to_string()),
+            let font_normal = parse_font(
+                &normal_font_data,
+                custom_font_normal_path,
+                "normal",
+                &diagnostics,
             )?;
+            let font_bold =
+                parse_font(&bold_font_data, custom_font_bold_path, "bold", &diagnostics)?;
 
             let font_normal_id = doc.add_font(&font_normal);
             let font_bold_id = doc.add_font(&font_bold);
 
+            let coverage = HashMap::from([
+                (
+                    font_normal_id.clone(),
+                    GlyphCoverage::from_font_bytes(&normal_font_data),
+                ),
+                (
+                    font_bold_id.clone(),
+                    GlyphCoverage::from_font_bytes(&bold_font_data),
+                ),
+            ]);
+
             Ok(FontBundle {
                 normal: font_normal_id,
                 bold: font_bold_id,
                 normal_fallback: None,
                 bold_fallback: None,
+                coverage,
+                diagnostics,
+                images: ImageCache::default(),
             })
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn draw_address(
     pdf_properties: &DocumentPropertiesDefault,
     translation: &Dictionary,
@@ -464,6 +984,9 @@ pub fn draw_address(
     current_y
 }
 
+/// Shipping and billing address side by side from `start_y`. Returns the operations
+/// and the y position of the separator line below them.
+#[allow(clippy::too_many_arguments)]
 pub fn draw_addresses(
     pdf_properties: &DocumentPropertiesDefault,
     translation: &Dictionary,
@@ -471,6 +994,7 @@ pub fn draw_addresses(
     billing_address: &Address,
     shipping_label: &str,
     billing_label: &str,
+    start_y: i32,
     fonts: &FontBundle,
 ) -> (Vec<Op>, i32) {
     let mut ops = vec![];
@@ -480,7 +1004,7 @@ pub fn draw_addresses(
         translation,
         &mut ops,
         50,
-        610,
+        start_y,
         shipping_label,
         shipping_address,
         fonts,
@@ -490,7 +1014,7 @@ pub fn draw_addresses(
         translation,
         &mut ops,
         300,
-        610,
+        start_y,
         billing_label,
         billing_address,
         fonts,
@@ -574,15 +1098,20 @@ pub fn draw_table_header_background(
 }
 
 pub fn draw_horizontal_line(y: i32) -> Op {
+    draw_line(50, 545, y)
+}
+
+/// A horizontal line from `x1` to `x2` at `y`, e.g. to sign on
+pub fn draw_line(x1: i32, x2: i32, y: i32) -> Op {
     Op::DrawLine {
         line: Line {
             points: vec![
                 LinePoint {
-                    p: Point::new(Mm(50.0 * 0.352778), Mm(y as f32 * 0.352778)),
+                    p: Point::new(Mm(x1 as f32 * 0.352778), Mm(y as f32 * 0.352778)),
                     bezier: false,
                 },
                 LinePoint {
-                    p: Point::new(Mm(545.0 * 0.352778), Mm(y as f32 * 0.352778)),
+                    p: Point::new(Mm(x2 as f32 * 0.352778), Mm(y as f32 * 0.352778)),
                     bezier: false,
                 },
             ],
@@ -0,0 +1,102 @@
+//! Orders and line items shared by the unit tests.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::calculator::{Calculator, LineInput};
+use crate::types::{
+    Address, DocumentProperties, Order, OrderLineItem, OrderStatus, PricingMode, TaxTreatment,
+};
+
+pub fn address(name: &str) -> Address {
+    Address {
+        recipient_name: Some(name.to_string()),
+        company_name: None,
+        street: "Musterstraße 1".to_string(),
+        street2: None,
+        city: "Berlin".to_string(),
+        state: "Berlin".to_string(),
+        country: "Germany".to_string(),
+        zip: "10115".to_string(),
+        phone: None,
+        vat_number: None,
+    }
+}
+
+pub fn line(id: &str, quantity: i64, unit_price: Decimal, tax_rate: Decimal) -> LineInput {
+    LineInput {
+        id: id.to_string(),
+        title: format!("Item {}", id),
+        sku: None,
+        quantity,
+        unit_price,
+        unit_discount: Decimal::ZERO,
+        tax_rate,
+        tax_category: None,
+    }
+}
+
+/// An order whose amounts are calculated from `lines` and `shipping_total` (taxed at
+/// `shipping_tax_rate`) by `calculator`
+pub fn order(
+    calculator: &Calculator,
+    lines: &[LineInput],
+    shipping_total: Decimal,
+    shipping_tax_rate: Decimal,
+) -> (Order, Vec<OrderLineItem>) {
+    let created_at = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    let mut order = Order {
+        id: "ORD-1".to_string(),
+        shipping_address: address("Jane Doe"),
+        billing_address: address("Jane Doe"),
+        customer_number: None,
+        customer_reference: None,
+        contact_person: None,
+        cost_center: None,
+        custom_fields: vec![],
+        currency: "EUR".to_string(),
+        status: OrderStatus::Completed,
+        shipping_method: "Standard".to_string(),
+        pricing_mode: PricingMode::Net,
+        tax_treatment: TaxTreatment::Standard,
+        shipping_total: Decimal::ZERO,
+        shipping_tax_rate: None,
+        shipping_tax_category: None,
+        subtotal_before_discount: Decimal::ZERO,
+        discount_total: Decimal::ZERO,
+        subtotal: Decimal::ZERO,
+        tax_total: Decimal::ZERO,
+        tax_lines: vec![],
+        total: Decimal::ZERO,
+        notes: None,
+        payment: None,
+        invoice: None,
+        created_at,
+        updated_at: created_at,
+    };
+    let calculation = calculator.calculate(lines, shipping_total, shipping_tax_rate);
+    calculation.totals.apply_to(&mut order);
+    (order, calculation.items)
+}
+
+/// No custom fonts, images, footer or checks
+pub fn properties() -> DocumentProperties {
+    DocumentProperties {
+        font_normal_path: None,
+        font_bold_path: None,
+        background_color: None,
+        font_size_title: None,
+        font_size_body: None,
+        font_size_label: None,
+        watermark: None,
+        signature_image: None,
+        seal_image: None,
+        strict_glyphs: None,
+        validate: None,
+        small_business_exemption: None,
+        seller: None,
+    }
+}
@@ -1,7 +1,10 @@
 use rust_decimal::Decimal;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::calculator::Calculator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Language {
+    #[default]
     English,
     German,
     French,
@@ -12,6 +15,7 @@ pub enum Language {
 }
 
 impl Language {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "en" | "english" => Some(Language::English),
@@ -50,12 +54,6 @@ impl Language {
     }
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Language::English
-    }
-}
-
 #[derive(Clone)]
 pub struct Address {
     pub recipient_name: Option<String>,
@@ -70,25 +68,260 @@ pub struct Address {
     pub company_name: Option<String>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct BankAccount {
+    pub bank_name: String,
+    // International accounts
+    pub iban: Option<String>,
+    pub bic: Option<String>,
+    // Domestic accounts, e.g. US account and ABA routing number or UK sort code
+    pub account_number: Option<String>,
+    pub routing_number: Option<String>,
+    // None = not printed
+    pub account_holder: Option<String>,
+}
+
+/// When and how an invoice is to be paid
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct PaymentInfo {
+    pub due_date: Option<chrono::NaiveDate>,
+    // e.g. "Payable within 14 days without deduction"
+    pub terms: Option<String>,
+    // Accounts for the bank transfer
+    pub bank_accounts: Vec<BankAccount>,
+}
+
+/// Legal details of the seller, printed in the footer of every page
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct SellerInfo {
+    pub legal_name: String,
+    // e.g. "Amtsgericht Frankfurt am Main"
+    pub registration_court: Option<String>,
+    // Label and number, e.g. ("HRB", "12345") or ("RCS Paris", "123 456 789")
+    pub registration_ids: Vec<(String, String)>,
+    // National tax number, e.g. Steuernummer or codice fiscale
+    pub tax_id: Option<String>,
+    pub vat_number: Option<String>,
+    pub directors: Vec<String>,
+    // Formatted, e.g. "EUR 25,000.00"
+    pub share_capital: Option<String>,
+    pub bank_accounts: Vec<BankAccount>,
+    pub website: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+/// When the goods were delivered or the service was performed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupplyDate {
+    Date(chrono::NaiveDate),
+    // Inclusive
+    Period {
+        start: chrono::NaiveDate,
+        end: chrono::NaiveDate,
+    },
+}
+
+/// Invoice number and dates, which follow their own sequence and calendar
+/// rather than the order's id and creation time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvoiceMeta {
+    // From a gapless sequence, e.g. "2023-0042"
+    pub number: String,
+    pub issue_date: chrono::NaiveDate,
+    // None = not printed, e.g. when it equals the issue date
+    pub supply_date: Option<SupplyDate>,
+}
+
+/// How a line or the shipping is treated for VAT
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TaxCategory {
+    // Taxed at the given rate
+    #[default]
+    Standard,
+    // Taxable, but at 0%
+    ZeroRated,
+    // Exempt from VAT
+    Exempt,
+    // Tax is accounted for by the buyer
+    ReverseCharge,
+    // Outside the scope of VAT
+    OutOfScope,
+}
+
+/// Whether prices on the document are shown without or with tax
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PricingMode {
+    // Prices exclude tax; tax is added in the totals (B2B)
+    #[default]
+    Net,
+    // Prices include tax; the totals list the tax included (B2C)
+    Gross,
+}
+
+/// Small-business VAT scheme of the seller's country. Decides which legal notice is
+/// printed; the notice itself is localized via Dictionary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SmallBusinessScheme {
+    // Kleinunternehmerregelung, § 19 UStG
+    Germany,
+    // Franchise en base de TVA, art. 293 B du CGI
+    France,
+    // Regime forfettario, art. 1, c. 54-89, L. 190/2014
+    Italy,
+    // Regime especial de isenção, art. 53.º do CIVA
+    Portugal,
+    // Any other scheme, with the generic `small_business_notice`
+    Other,
+    // Notice rendered as-is, e.g. for Austria's § 6 Abs. 1 Z 27 UStG
+    Custom(String),
+}
+
+/// VAT treatment of the whole order; anything but `Standard` prints a legal notice
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TaxTreatment {
+    #[default]
+    Standard,
+    // B2B service within the EU; the buyer accounts for the VAT
+    ReverseCharge,
+    // B2B delivery of goods to another EU country
+    IntraCommunity,
+    // Delivery outside the EU
+    Export,
+    // Supply exempt from VAT
+    Exempt,
+}
+
+/// Type of a named tax; the label is localized via Dictionary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TaxKind {
+    Vat,
+    // Canadian goods and services tax
+    Gst,
+    // Canadian harmonized sales tax
+    Hst,
+    // Canadian provincial sales tax
+    Pst,
+    // Quebec sales tax
+    Qst,
+    // US sales tax by jurisdiction
+    StateSalesTax,
+    CountySalesTax,
+    CitySalesTax,
+    // Rendered as-is
+    Custom(String),
+}
+
+/// Status of the order; the label is localized via Dictionary
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderStatus {
+    Pending,
+    Paid,
+    Processing,
+    Shipped,
+    Completed,
+    Cancelled,
+    Refunded,
+    // Rendered as-is
+    Custom(String),
+}
+
+/// One of several taxes charged on an order or line, e.g. GST and PST
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaxLine {
+    pub kind: TaxKind,
+    // Appended to the label, e.g. "CA" or "Los Angeles"
+    pub jurisdiction: Option<String>,
+    // In percent, e.g. 5 for 5% (None = not shown)
+    pub rate: Option<Decimal>,
+    pub amount: Decimal,
+    // Charged on the net amount plus the taxes listed before it
+    pub compound: bool,
+}
+
 #[derive(Clone)]
 pub struct Order {
     pub id: String,
     pub shipping_address: Address,
     pub billing_address: Address,
+    // Our number for the customer
+    pub customer_number: Option<String>,
+    // The customer's purchase order number or reference
+    pub customer_reference: Option<String>,
+    // Person at the customer the document is addressed to
+    pub contact_person: Option<String>,
+    // The customer's cost center to book the invoice on
+    pub cost_center: Option<String>,
+    // Extra (label, value) lines in the header, in this order, e.g. ("Project:", "P-17")
+    pub custom_fields: Vec<(String, String)>,
     pub currency: String,
-    pub status: String,
+    pub status: OrderStatus,
     pub shipping_method: String,
+    // Amounts stored on the order and items are always net
+    pub pricing_mode: PricingMode,
+    // Anything but Standard prints a legal notice
+    pub tax_treatment: TaxTreatment,
     pub shipping_total: Decimal,
+    // In percent, e.g. 19 for 19% (None = shipping is left out of the tax summary)
+    pub shipping_tax_rate: Option<Decimal>,
+    // None = Standard
+    pub shipping_tax_category: Option<TaxCategory>,
     pub subtotal_before_discount: Decimal,
     pub discount_total: Decimal,
     pub subtotal: Decimal,
     pub tax_total: Decimal,
+    // Breakdown of `tax_total` into named taxes (empty = a single tax row)
+    pub tax_lines: Vec<TaxLine>,
     pub total: Decimal,
     pub notes: Option<String>,
+    // Due date, terms and bank details (None = no payment information block)
+    pub payment: Option<PaymentInfo>,
+    // Invoice number and dates (None = the order id and creation date)
+    pub invoice: Option<InvoiceMeta>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
 
+impl Order {
+    /// `invoice.number`, or the order id without [`InvoiceMeta`]
+    pub fn invoice_number(&self) -> &str {
+        match &self.invoice {
+            Some(invoice) => &invoice.number,
+            None => &self.id,
+        }
+    }
+
+    /// `invoice.issue_date`, or the day the order was created without [`InvoiceMeta`]
+    pub fn invoice_date(&self) -> chrono::NaiveDate {
+        match &self.invoice {
+            Some(invoice) => invoice.issue_date,
+            None => self.created_at.date(),
+        }
+    }
+
+    /// Amounts including tax. Line totals are taken as they are, so the gross subtotal
+    /// plus the gross shipping always adds up to `total`.
+    pub fn gross_totals(&self, items: &[OrderLineItem]) -> GrossTotals {
+        let subtotal: Decimal = items.iter().map(|item| item.total).sum();
+        let discount_total = items.iter().map(|item| item.gross_discount_total()).sum();
+        GrossTotals {
+            subtotal_before_discount: subtotal + discount_total,
+            discount_total,
+            subtotal,
+            shipping_total: self.total - subtotal,
+        }
+    }
+}
+
+/// Order amounts including tax, for [`PricingMode::Gross`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrossTotals {
+    pub subtotal_before_discount: Decimal,
+    pub discount_total: Decimal,
+    pub subtotal: Decimal,
+    pub shipping_total: Decimal,
+}
+
 #[derive(Clone)]
 pub struct OrderLineItem {
     pub id: String,
@@ -102,7 +335,73 @@ pub struct OrderLineItem {
     pub discount_total: Decimal,
     pub subtotal: Decimal,
     pub tax_total: Decimal,
+    // Breakdown of `tax_total` into named taxes
+    pub tax_lines: Vec<TaxLine>,
     pub total: Decimal,
+    // In percent, e.g. 19 for 19% (None = line is left out of the tax summary)
+    pub tax_rate: Option<Decimal>,
+    // None = Standard
+    pub tax_category: Option<TaxCategory>,
+}
+
+impl OrderLineItem {
+    /// Net to gross: 1 + `tax_rate`, or `total / subtotal` without a rate
+    fn gross_factor(&self) -> Decimal {
+        match self.tax_rate {
+            Some(rate) => Decimal::ONE + rate / Decimal::ONE_HUNDRED,
+            None if self.subtotal.is_zero() => Decimal::ONE,
+            None => self.total / self.subtotal,
+        }
+    }
+
+    /// `unit_price` including tax, rounded half-up
+    pub fn gross_unit_price(&self) -> Decimal {
+        Calculator::default().round(self.unit_price * self.gross_factor())
+    }
+
+    /// `discount_total` including tax, rounded half-up
+    pub fn gross_discount_total(&self) -> Decimal {
+        Calculator::default().round(self.discount_total * self.gross_factor())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatermarkContent {
+    // Standard stamps, localized via Dictionary
+    Paid,
+    Draft,
+    Copy,
+    Cancelled,
+    Void,
+    // Custom text, rendered as-is
+    Text(String),
+    // PNG/SVG image data
+    Image(Vec<u8>),
+}
+
+#[derive(Clone)]
+pub struct Watermark {
+    pub content: WatermarkContent,
+    // Counter-clockwise, in degrees (None = 45.0)
+    pub rotation: Option<f32>,
+    // 0.0 (invisible) to 1.0 (opaque) (None = 0.15)
+    pub opacity: Option<f32>,
+    // Text color (None = "0.8 0.1 0.1" (red))
+    pub color: Option<(f32, f32, f32)>,
+    // Text size (None = 80.0)
+    pub font_size: Option<f32>,
+}
+
+impl Watermark {
+    pub fn new(content: WatermarkContent) -> Self {
+        Self {
+            content,
+            rotation: None,
+            opacity: None,
+            color: None,
+            font_size: None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -116,6 +415,21 @@ pub struct DocumentProperties {
     pub font_size_title: Option<f32>,
     pub font_size_body: Option<f32>,
     pub font_size_label: Option<f32>,
+    // Stamp rendered across every page (None = no watermark)
+    pub watermark: Option<Watermark>,
+    // Authorized signature image data, PNG/SVG (None = blank signature line)
+    pub signature_image: Option<Vec<u8>>,
+    // Company seal / stamp image data, PNG/SVG
+    pub seal_image: Option<Vec<u8>>,
+    // Fail instead of drawing empty boxes for characters no font covers (None = false)
+    pub strict_glyphs: Option<bool>,
+    // Refuse to render orders failing `Order::validate` (None = false)
+    pub validate: Option<bool>,
+    // Small-business VAT exemption of the seller's country (e.g. § 19 UStG): hide tax
+    // and print the scheme's legal notice (None = no exemption)
+    pub small_business_exemption: Option<SmallBusinessScheme>,
+    // Legal footer on every page (None = no footer)
+    pub seller: Option<SellerInfo>,
 }
 
 #[derive(Clone)]
@@ -129,6 +443,13 @@ pub struct DocumentPropertiesDefault {
     pub font_size_title: f32,
     pub font_size_body: f32,
     pub font_size_label: f32,
+    pub watermark: Option<Watermark>,
+    pub signature_image: Option<Vec<u8>>,
+    pub seal_image: Option<Vec<u8>>,
+    pub strict_glyphs: bool,
+    pub validate: bool,
+    pub small_business_exemption: Option<SmallBusinessScheme>,
+    pub seller: Option<SellerInfo>,
 }
 
 impl DocumentProperties {
@@ -140,6 +461,13 @@ impl DocumentProperties {
             font_size_title: self.font_size_title.unwrap_or(20.0),
             font_size_body: self.font_size_body.unwrap_or(10.0),
             font_size_label: self.font_size_label.unwrap_or(10.0),
+            watermark: self.watermark,
+            signature_image: self.signature_image,
+            seal_image: self.seal_image,
+            strict_glyphs: self.strict_glyphs.unwrap_or(false),
+            validate: self.validate.unwrap_or(false),
+            small_business_exemption: self.small_business_exemption,
+            seller: self.seller,
         }
     }
 }
@@ -200,11 +528,84 @@ pub struct Dictionary {
     pub packer_verification_title: String,
     pub packed_by_label: String,
     pub signature_label: String,
+    pub authorized_by_label: String,
 
     // Proforma invoice specific
     pub estimated_total_label: String,
     pub proforma_notice: String,
     pub proforma_footer_notice: String,
+
+    // Tax summary
+    pub tax_rate_header: String,
+    pub net_amount_header: String,
+    pub tax_category_zero_rated: String,
+    pub tax_category_exempt: String,
+    pub tax_category_reverse_charge: String,
+    pub tax_category_out_of_scope: String,
+
+    // Tax types
+    pub tax_vat_label: String,
+    pub tax_gst_label: String,
+    pub tax_hst_label: String,
+    pub tax_pst_label: String,
+    pub tax_qst_label: String,
+    pub tax_state_label: String,
+    pub tax_county_label: String,
+    pub tax_city_label: String,
+    // Gross pricing; "{tax}" is replaced by e.g. "19% VAT"
+    pub tax_included_label: String,
+
+    // Tax treatment notices
+    pub reverse_charge_notice: String,
+    pub intra_community_notice: String,
+    pub export_notice: String,
+    pub exempt_notice: String,
+    pub small_business_notice: String,
+    pub small_business_notice_germany: String,
+    pub small_business_notice_france: String,
+    pub small_business_notice_italy: String,
+    pub small_business_notice_portugal: String,
+
+    // Seller footer
+    pub directors_label: String,
+    pub share_capital_label: String,
+    pub tax_id_label: String,
+    pub bank_label: String,
+
+    // Payment information
+    pub payment_info_label: String,
+    pub due_date_label: String,
+    pub account_holder_label: String,
+    pub iban_label: String,
+    pub bic_label: String,
+    pub account_number_label: String,
+    pub routing_number_label: String,
+
+    // Invoice dates
+    pub supply_date_label: String,
+    pub service_period_label: String,
+
+    // Customer references
+    pub customer_number_label: String,
+    pub customer_reference_label: String,
+    pub contact_person_label: String,
+    pub cost_center_label: String,
+
+    // Order status
+    pub status_pending: String,
+    pub status_paid: String,
+    pub status_processing: String,
+    pub status_shipped: String,
+    pub status_completed: String,
+    pub status_cancelled: String,
+    pub status_refunded: String,
+
+    // Watermarks
+    pub watermark_paid: String,
+    pub watermark_draft: String,
+    pub watermark_copy: String,
+    pub watermark_cancelled: String,
+    pub watermark_void: String,
 }
 
 impl Default for Dictionary {
@@ -253,6 +654,7 @@ impl Default for Dictionary {
             packer_verification_title: "PACKER VERIFICATION".to_string(),
             packed_by_label: "Packed by:".to_string(),
             signature_label: "Signature:".to_string(),
+            authorized_by_label: "Authorized by:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "Estimated Total".to_string(),
@@ -260,6 +662,80 @@ impl Default for Dictionary {
             proforma_footer_notice:
                 "NOTICE: This proforma invoice is an estimate only and not a request for payment."
                     .to_string(),
+
+            // Tax summary
+            tax_rate_header: "Tax Rate".to_string(),
+            net_amount_header: "Net".to_string(),
+            tax_category_zero_rated: "Zero-rated".to_string(),
+            tax_category_exempt: "Exempt".to_string(),
+            tax_category_reverse_charge: "Reverse charge".to_string(),
+            tax_category_out_of_scope: "Not taxable".to_string(),
+
+            // Tax types
+            tax_vat_label: "VAT".to_string(),
+            tax_gst_label: "GST".to_string(),
+            tax_hst_label: "HST".to_string(),
+            tax_pst_label: "PST".to_string(),
+            tax_qst_label: "QST".to_string(),
+            tax_state_label: "State Tax".to_string(),
+            tax_county_label: "County Tax".to_string(),
+            tax_city_label: "City Tax".to_string(),
+            tax_included_label: "incl. {tax}:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice:
+                "Reverse charge: VAT to be accounted for by the recipient (Art. 196 VAT Directive)"
+                    .to_string(),
+            intra_community_notice: "Tax-exempt intra-community supply (Art. 138 VAT Directive)"
+                .to_string(),
+            export_notice: "Tax-exempt export delivery (Art. 146 VAT Directive)".to_string(),
+            exempt_notice: "VAT-exempt supply (Art. 132 VAT Directive)".to_string(),
+            small_business_notice: "No VAT charged under the small business exemption scheme".to_string(),
+            small_business_notice_germany: "No VAT charged under § 19 UStG (small business exemption)".to_string(),
+            small_business_notice_france: "VAT not applicable, art. 293 B of the CGI".to_string(),
+            small_business_notice_italy: "VAT-exempt transaction under the flat-rate scheme (art. 1, para. 54-89, Law 190/2014)".to_string(),
+            small_business_notice_portugal: "VAT exempt under art. 53 of the CIVA".to_string(),
+
+            // Seller footer
+            directors_label: "Managing Directors:".to_string(),
+            share_capital_label: "Share Capital:".to_string(),
+            tax_id_label: "Tax No.:".to_string(),
+            bank_label: "Bank:".to_string(),
+
+            // Payment information
+            payment_info_label: "Payment Information:".to_string(),
+            due_date_label: "Due Date:".to_string(),
+            account_holder_label: "Account Holder:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "Account No.:".to_string(),
+            routing_number_label: "Routing No.:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "Delivery Date:".to_string(),
+            service_period_label: "Service Period:".to_string(),
+
+            // Customer references
+            customer_number_label: "Customer No.:".to_string(),
+            customer_reference_label: "PO Number:".to_string(),
+            contact_person_label: "Contact:".to_string(),
+            cost_center_label: "Cost Center:".to_string(),
+
+            // Order status
+            status_pending: "Pending".to_string(),
+            status_paid: "Paid".to_string(),
+            status_processing: "Processing".to_string(),
+            status_shipped: "Shipped".to_string(),
+            status_completed: "Completed".to_string(),
+            status_cancelled: "Cancelled".to_string(),
+            status_refunded: "Refunded".to_string(),
+
+            // Watermarks
+            watermark_paid: "PAID".to_string(),
+            watermark_draft: "DRAFT".to_string(),
+            watermark_copy: "COPY".to_string(),
+            watermark_cancelled: "CANCELLED".to_string(),
+            watermark_void: "VOID".to_string(),
         }
     }
 }
@@ -310,11 +786,83 @@ impl Dictionary {
             packer_verification_title: "VERPACKUNGSBESTÄTIGUNG".to_string(),
             packed_by_label: "Verpackt von:".to_string(),
             signature_label: "Unterschrift:".to_string(),
+            authorized_by_label: "Freigegeben von:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "Geschätzte Summe".to_string(),
             proforma_notice: "Dies ist keine Rechnung - nur zur Schätzung".to_string(),
             proforma_footer_notice: "HINWEIS: Diese Proforma-Rechnung ist nur eine Schätzung und keine Zahlungsaufforderung.".to_string(),
+
+            // Tax summary
+            tax_rate_header: "Steuersatz".to_string(),
+            net_amount_header: "Netto".to_string(),
+            tax_category_zero_rated: "Nullsatz".to_string(),
+            tax_category_exempt: "Steuerfrei".to_string(),
+            tax_category_reverse_charge: "Reverse Charge".to_string(),
+            tax_category_out_of_scope: "Nicht steuerbar".to_string(),
+
+            // Tax types
+            tax_vat_label: "MwSt.".to_string(),
+            tax_gst_label: "GST".to_string(),
+            tax_hst_label: "HST".to_string(),
+            tax_pst_label: "PST".to_string(),
+            tax_qst_label: "QST".to_string(),
+            tax_state_label: "Staatssteuer".to_string(),
+            tax_county_label: "County-Steuer".to_string(),
+            tax_city_label: "Stadtsteuer".to_string(),
+            tax_included_label: "inkl. {tax}:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice: "Steuerschuldnerschaft des Leistungsempfängers (Art. 196 MwStSystRL)".to_string(),
+            intra_community_notice: "Steuerfreie innergemeinschaftliche Lieferung (§ 4 Nr. 1b UStG)".to_string(),
+            export_notice: "Steuerfreie Ausfuhrlieferung (§ 4 Nr. 1a UStG)".to_string(),
+            exempt_notice: "Steuerfreie Leistung gemäß § 4 UStG".to_string(),
+            small_business_notice: "Keine Umsatzsteuer aufgrund der Kleinunternehmerregelung".to_string(),
+            small_business_notice_germany: "Gemäß § 19 UStG wird keine Umsatzsteuer berechnet".to_string(),
+            small_business_notice_france: "Keine Umsatzsteuer gemäß Art. 293 B CGI".to_string(),
+            small_business_notice_italy: "Umsatzsteuerfrei im Pauschalregime (Art. 1, Abs. 54-89, Gesetz 190/2014)".to_string(),
+            small_business_notice_portugal: "Umsatzsteuerfrei gemäß Art. 53 CIVA".to_string(),
+
+            // Seller footer
+            directors_label: "Geschäftsführer:".to_string(),
+            share_capital_label: "Stammkapital:".to_string(),
+            tax_id_label: "Steuernummer:".to_string(),
+            bank_label: "Bank:".to_string(),
+
+            // Payment information
+            payment_info_label: "Zahlungsinformationen:".to_string(),
+            due_date_label: "Fällig am:".to_string(),
+            account_holder_label: "Kontoinhaber:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "Kontonummer:".to_string(),
+            routing_number_label: "Bankleitzahl:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "Lieferdatum:".to_string(),
+            service_period_label: "Leistungszeitraum:".to_string(),
+
+            // Customer references
+            customer_number_label: "Kundennummer:".to_string(),
+            customer_reference_label: "Ihre Bestellnr.:".to_string(),
+            contact_person_label: "Ansprechpartner:".to_string(),
+            cost_center_label: "Kostenstelle:".to_string(),
+
+            // Order status
+            status_pending: "Ausstehend".to_string(),
+            status_paid: "Bezahlt".to_string(),
+            status_processing: "In Bearbeitung".to_string(),
+            status_shipped: "Versandt".to_string(),
+            status_completed: "Abgeschlossen".to_string(),
+            status_cancelled: "Storniert".to_string(),
+            status_refunded: "Erstattet".to_string(),
+
+            // Watermarks
+            watermark_paid: "BEZAHLT".to_string(),
+            watermark_draft: "ENTWURF".to_string(),
+            watermark_copy: "KOPIE".to_string(),
+            watermark_cancelled: "STORNIERT".to_string(),
+            watermark_void: "UNGÜLTIG".to_string(),
         }
     }
 
@@ -363,11 +911,83 @@ impl Dictionary {
             packer_verification_title: "VÉRIFICATION EMBALLAGE".to_string(),
             packed_by_label: "Emballé par:".to_string(),
             signature_label: "Signature:".to_string(),
+            authorized_by_label: "Autorisé par:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "Total estimé".to_string(),
             proforma_notice: "Ceci n'est pas une facture - à des fins d'estimation uniquement".to_string(),
             proforma_footer_notice: "AVIS: Cette facture proforma est seulement une estimation et non une demande de paiement.".to_string(),
+
+            // Tax summary
+            tax_rate_header: "Taux TVA".to_string(),
+            net_amount_header: "Base HT".to_string(),
+            tax_category_zero_rated: "Taux zéro".to_string(),
+            tax_category_exempt: "Exonéré".to_string(),
+            tax_category_reverse_charge: "Autoliquidation".to_string(),
+            tax_category_out_of_scope: "Hors champ".to_string(),
+
+            // Tax types
+            tax_vat_label: "TVA".to_string(),
+            tax_gst_label: "TPS".to_string(),
+            tax_hst_label: "TVH".to_string(),
+            tax_pst_label: "TVP".to_string(),
+            tax_qst_label: "TVQ".to_string(),
+            tax_state_label: "Taxe d'État".to_string(),
+            tax_county_label: "Taxe de comté".to_string(),
+            tax_city_label: "Taxe municipale".to_string(),
+            tax_included_label: "dont {tax}:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice: "Autoliquidation : TVA due par le preneur (art. 196 de la directive TVA)".to_string(),
+            intra_community_notice: "Exonération de TVA, livraison intracommunautaire (art. 262 ter I du CGI)".to_string(),
+            export_notice: "Exonération de TVA, exportation (art. 262 I du CGI)".to_string(),
+            exempt_notice: "Exonération de TVA (art. 261 du CGI)".to_string(),
+            small_business_notice: "TVA non applicable, régime des petites entreprises".to_string(),
+            small_business_notice_germany: "TVA non facturée en application du § 19 UStG (régime des petites entreprises)".to_string(),
+            small_business_notice_france: "TVA non applicable, art. 293 B du CGI".to_string(),
+            small_business_notice_italy: "Opération exonérée de TVA, régime forfaitaire (art. 1, al. 54-89, loi 190/2014)".to_string(),
+            small_business_notice_portugal: "Exonéré de TVA, art. 53 du CIVA".to_string(),
+
+            // Seller footer
+            directors_label: "Dirigeants:".to_string(),
+            share_capital_label: "Capital social:".to_string(),
+            tax_id_label: "N° fiscal:".to_string(),
+            bank_label: "Banque:".to_string(),
+
+            // Payment information
+            payment_info_label: "Informations de paiement:".to_string(),
+            due_date_label: "Date d'échéance:".to_string(),
+            account_holder_label: "Titulaire du compte:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "N° de compte:".to_string(),
+            routing_number_label: "Code banque:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "Date de livraison:".to_string(),
+            service_period_label: "Période de prestation:".to_string(),
+
+            // Customer references
+            customer_number_label: "N° client:".to_string(),
+            customer_reference_label: "N° de commande:".to_string(),
+            contact_person_label: "Contact:".to_string(),
+            cost_center_label: "Centre de coût:".to_string(),
+
+            // Order status
+            status_pending: "En attente".to_string(),
+            status_paid: "Payée".to_string(),
+            status_processing: "En cours de traitement".to_string(),
+            status_shipped: "Expédiée".to_string(),
+            status_completed: "Terminée".to_string(),
+            status_cancelled: "Annulée".to_string(),
+            status_refunded: "Remboursée".to_string(),
+
+            // Watermarks
+            watermark_paid: "PAYÉ".to_string(),
+            watermark_draft: "BROUILLON".to_string(),
+            watermark_copy: "COPIE".to_string(),
+            watermark_cancelled: "ANNULÉ".to_string(),
+            watermark_void: "NUL".to_string(),
         }
     }
 
@@ -416,6 +1036,7 @@ impl Dictionary {
             packer_verification_title: "VERIFICACIÓN DE EMPAQUETADO".to_string(),
             packed_by_label: "Empaquetado por:".to_string(),
             signature_label: "Firma:".to_string(),
+            authorized_by_label: "Autorizado por:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "Total estimado".to_string(),
@@ -423,6 +1044,81 @@ impl Dictionary {
             proforma_footer_notice:
                 "AVISO: Esta factura proforma es solo una estimación y no una solicitud de pago."
                     .to_string(),
+
+            // Tax summary
+            tax_rate_header: "Tipo IVA".to_string(),
+            net_amount_header: "Base".to_string(),
+            tax_category_zero_rated: "Tipo cero".to_string(),
+            tax_category_exempt: "Exento".to_string(),
+            tax_category_reverse_charge: "Inversión sujeto pasivo".to_string(),
+            tax_category_out_of_scope: "No sujeto".to_string(),
+
+            // Tax types
+            tax_vat_label: "IVA".to_string(),
+            tax_gst_label: "GST".to_string(),
+            tax_hst_label: "HST".to_string(),
+            tax_pst_label: "PST".to_string(),
+            tax_qst_label: "QST".to_string(),
+            tax_state_label: "Impuesto estatal".to_string(),
+            tax_county_label: "Impuesto del condado".to_string(),
+            tax_city_label: "Impuesto municipal".to_string(),
+            tax_included_label: "{tax} incluido:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice: "Inversión del sujeto pasivo (art. 196 Directiva IVA)"
+                .to_string(),
+            intra_community_notice: "Entrega intracomunitaria exenta (art. 25 LIVA)".to_string(),
+            export_notice: "Exportación exenta (art. 21 LIVA)".to_string(),
+            exempt_notice: "Operación exenta de IVA (art. 20 LIVA)".to_string(),
+            small_business_notice: "IVA no aplicable: régimen de franquicia del IVA".to_string(),
+            small_business_notice_germany:
+                "IVA no facturado según el § 19 UStG (régimen de pequeñas empresas)".to_string(),
+            small_business_notice_france: "IVA no aplicable, art. 293 B del CGI".to_string(),
+            small_business_notice_italy:
+                "Operación exenta de IVA, régimen forfettario (art. 1, c. 54-89, Ley 190/2014)"
+                    .to_string(),
+            small_business_notice_portugal: "IVA exento, art. 53.º del CIVA".to_string(),
+
+            // Seller footer
+            directors_label: "Administradores:".to_string(),
+            share_capital_label: "Capital social:".to_string(),
+            tax_id_label: "NIF:".to_string(),
+            bank_label: "Banco:".to_string(),
+
+            // Payment information
+            payment_info_label: "Información de pago:".to_string(),
+            due_date_label: "Fecha de vencimiento:".to_string(),
+            account_holder_label: "Titular de la cuenta:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "N.º de cuenta:".to_string(),
+            routing_number_label: "Código bancario:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "Fecha de entrega:".to_string(),
+            service_period_label: "Período de servicio:".to_string(),
+
+            // Customer references
+            customer_number_label: "N.º de cliente:".to_string(),
+            customer_reference_label: "N.º de pedido:".to_string(),
+            contact_person_label: "Contacto:".to_string(),
+            cost_center_label: "Centro de coste:".to_string(),
+
+            // Order status
+            status_pending: "Pendiente".to_string(),
+            status_paid: "Pagado".to_string(),
+            status_processing: "En proceso".to_string(),
+            status_shipped: "Enviado".to_string(),
+            status_completed: "Completado".to_string(),
+            status_cancelled: "Cancelado".to_string(),
+            status_refunded: "Reembolsado".to_string(),
+
+            // Watermarks
+            watermark_paid: "PAGADO".to_string(),
+            watermark_draft: "BORRADOR".to_string(),
+            watermark_copy: "COPIA".to_string(),
+            watermark_cancelled: "CANCELADO".to_string(),
+            watermark_void: "ANULADO".to_string(),
         }
     }
 
@@ -471,11 +1167,83 @@ impl Dictionary {
             packer_verification_title: "VERIFICAÇÃO DE EMBALAGEM".to_string(),
             packed_by_label: "Embalado por:".to_string(),
             signature_label: "Assinatura:".to_string(),
+            authorized_by_label: "Autorizado por:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "Total estimado".to_string(),
             proforma_notice: "Esta não é uma fatura - apenas para fins de estimativa".to_string(),
             proforma_footer_notice: "AVISO: Esta fatura proforma é apenas uma estimativa e não uma solicitação de pagamento.".to_string(),
+
+            // Tax summary
+            tax_rate_header: "Taxa IVA".to_string(),
+            net_amount_header: "Base".to_string(),
+            tax_category_zero_rated: "Taxa zero".to_string(),
+            tax_category_exempt: "Isento".to_string(),
+            tax_category_reverse_charge: "Autoliquidação".to_string(),
+            tax_category_out_of_scope: "Não sujeito".to_string(),
+
+            // Tax types
+            tax_vat_label: "IVA".to_string(),
+            tax_gst_label: "GST".to_string(),
+            tax_hst_label: "HST".to_string(),
+            tax_pst_label: "PST".to_string(),
+            tax_qst_label: "QST".to_string(),
+            tax_state_label: "Imposto estadual".to_string(),
+            tax_county_label: "Imposto do condado".to_string(),
+            tax_city_label: "Imposto municipal".to_string(),
+            tax_included_label: "{tax} incluído:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice: "Autoliquidação: IVA devido pelo adquirente (art. 196.º da Diretiva IVA)".to_string(),
+            intra_community_notice: "Isento: transmissão intracomunitária (art. 14.º do RITI)".to_string(),
+            export_notice: "Isento: exportação (art. 14.º do CIVA)".to_string(),
+            exempt_notice: "Isento de IVA (art. 9.º do CIVA)".to_string(),
+            small_business_notice: "IVA não aplicável: regime das pequenas empresas".to_string(),
+            small_business_notice_germany: "IVA não faturado nos termos do § 19 UStG (regime de pequenas empresas)".to_string(),
+            small_business_notice_france: "IVA não aplicável, art. 293 B do CGI".to_string(),
+            small_business_notice_italy: "Operação isenta de IVA, regime forfettario (art. 1, c. 54-89, Lei 190/2014)".to_string(),
+            small_business_notice_portugal: "IVA: regime de isenção (art. 53.º do CIVA)".to_string(),
+
+            // Seller footer
+            directors_label: "Administradores:".to_string(),
+            share_capital_label: "Capital social:".to_string(),
+            tax_id_label: "NIF:".to_string(),
+            bank_label: "Banco:".to_string(),
+
+            // Payment information
+            payment_info_label: "Informações de pagamento:".to_string(),
+            due_date_label: "Data de vencimento:".to_string(),
+            account_holder_label: "Titular da conta:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "N.º da conta:".to_string(),
+            routing_number_label: "Código do banco:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "Data de entrega:".to_string(),
+            service_period_label: "Período de serviço:".to_string(),
+
+            // Customer references
+            customer_number_label: "N.º de cliente:".to_string(),
+            customer_reference_label: "N.º da encomenda:".to_string(),
+            contact_person_label: "Contacto:".to_string(),
+            cost_center_label: "Centro de custo:".to_string(),
+
+            // Order status
+            status_pending: "Pendente".to_string(),
+            status_paid: "Pago".to_string(),
+            status_processing: "Em processamento".to_string(),
+            status_shipped: "Enviado".to_string(),
+            status_completed: "Concluído".to_string(),
+            status_cancelled: "Cancelado".to_string(),
+            status_refunded: "Reembolsado".to_string(),
+
+            // Watermarks
+            watermark_paid: "PAGO".to_string(),
+            watermark_draft: "RASCUNHO".to_string(),
+            watermark_copy: "CÓPIA".to_string(),
+            watermark_cancelled: "CANCELADO".to_string(),
+            watermark_void: "ANULADO".to_string(),
         }
     }
 
@@ -524,12 +1292,88 @@ impl Dictionary {
             packer_verification_title: "การตรวจสอบการแพ็ค".to_string(),
             packed_by_label: "แพ็คโดย:".to_string(),
             signature_label: "ลายเซ็น:".to_string(),
+            authorized_by_label: "ผู้มีอำนาจลงนาม:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "ยอดรวมโดยประมาณ".to_string(),
             proforma_notice: "นี่ไม่ใช่บิล - สำหรับวัตถุประสงค์ในการประมาณการเท่านั้น".to_string(),
             proforma_footer_notice:
                 "ประกาศ: ใบแจ้งหนี้เบื้องต้นนี้เป็นเพียงการประมาณการเท่านั้น ไม่ใช่การร้องขอการชำระเงิน".to_string(),
+
+            // Tax summary
+            tax_rate_header: "อัตราภาษี".to_string(),
+            net_amount_header: "ยอดสุทธิ".to_string(),
+            tax_category_zero_rated: "อัตราศูนย์".to_string(),
+            tax_category_exempt: "ยกเว้นภาษี".to_string(),
+            tax_category_reverse_charge: "ผู้ซื้อนำส่งภาษี".to_string(),
+            tax_category_out_of_scope: "ไม่อยู่ในขอบเขตภาษี".to_string(),
+
+            // Tax types
+            tax_vat_label: "ภาษีมูลค่าเพิ่ม".to_string(),
+            tax_gst_label: "GST".to_string(),
+            tax_hst_label: "HST".to_string(),
+            tax_pst_label: "PST".to_string(),
+            tax_qst_label: "QST".to_string(),
+            tax_state_label: "ภาษีรัฐ".to_string(),
+            tax_county_label: "ภาษีเคาน์ตี".to_string(),
+            tax_city_label: "ภาษีเมือง".to_string(),
+            tax_included_label: "รวม{tax}:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice: "ผู้ซื้อเป็นผู้นำส่งภาษีมูลค่าเพิ่ม (Reverse charge, Art. 196 VAT Directive)"
+                .to_string(),
+            intra_community_notice:
+                "การส่งมอบสินค้าภายในสหภาพยุโรปที่ได้รับยกเว้นภาษี (Art. 138 VAT Directive)".to_string(),
+            export_notice: "การส่งออกที่ได้รับยกเว้นภาษีมูลค่าเพิ่ม".to_string(),
+            exempt_notice: "รายการที่ได้รับยกเว้นภาษีมูลค่าเพิ่ม".to_string(),
+            small_business_notice: "ผู้ประกอบการไม่ได้จดทะเบียนภาษีมูลค่าเพิ่ม".to_string(),
+            small_business_notice_germany: "ไม่เรียกเก็บภาษีมูลค่าเพิ่มตาม § 19 UStG (ผู้ประกอบการขนาดเล็ก)"
+                .to_string(),
+            small_business_notice_france: "ไม่มีภาษีมูลค่าเพิ่ม ตามมาตรา 293 B ของ CGI".to_string(),
+            small_business_notice_italy:
+                "ได้รับยกเว้นภาษีมูลค่าเพิ่มตามระบบเหมาจ่าย (มาตรา 1 วรรค 54-89 กฎหมาย 190/2014)".to_string(),
+            small_business_notice_portugal: "ได้รับยกเว้นภาษีมูลค่าเพิ่มตามมาตรา 53 ของ CIVA".to_string(),
+
+            // Seller footer
+            directors_label: "กรรมการผู้จัดการ:".to_string(),
+            share_capital_label: "ทุนจดทะเบียน:".to_string(),
+            tax_id_label: "เลขผู้เสียภาษี:".to_string(),
+            bank_label: "ธนาคาร:".to_string(),
+
+            // Payment information
+            payment_info_label: "ข้อมูลการชำระเงิน:".to_string(),
+            due_date_label: "วันครบกำหนด:".to_string(),
+            account_holder_label: "ชื่อบัญชี:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "เลขที่บัญชี:".to_string(),
+            routing_number_label: "รหัสธนาคาร:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "วันที่ส่งมอบ:".to_string(),
+            service_period_label: "ระยะเวลาให้บริการ:".to_string(),
+
+            // Customer references
+            customer_number_label: "รหัสลูกค้า:".to_string(),
+            customer_reference_label: "เลขที่ใบสั่งซื้อ:".to_string(),
+            contact_person_label: "ผู้ติดต่อ:".to_string(),
+            cost_center_label: "ศูนย์ต้นทุน:".to_string(),
+
+            // Order status
+            status_pending: "รอดำเนินการ".to_string(),
+            status_paid: "ชำระเงินแล้ว".to_string(),
+            status_processing: "กำลังดำเนินการ".to_string(),
+            status_shipped: "จัดส่งแล้ว".to_string(),
+            status_completed: "เสร็จสมบูรณ์".to_string(),
+            status_cancelled: "ยกเลิกแล้ว".to_string(),
+            status_refunded: "คืนเงินแล้ว".to_string(),
+
+            // Watermarks
+            watermark_paid: "ชำระแล้ว".to_string(),
+            watermark_draft: "ฉบับร่าง".to_string(),
+            watermark_copy: "สำเนา".to_string(),
+            watermark_cancelled: "ยกเลิก".to_string(),
+            watermark_void: "โมฆะ".to_string(),
         }
     }
 
@@ -578,6 +1422,7 @@ impl Dictionary {
             packer_verification_title: "VERIFICA IMBALLAGGIO".to_string(),
             packed_by_label: "Imballato da:".to_string(),
             signature_label: "Firma:".to_string(),
+            authorized_by_label: "Autorizzato da:".to_string(),
 
             // Proforma invoice specific
             estimated_total_label: "Totale stimato".to_string(),
@@ -585,9 +1430,184 @@ impl Dictionary {
             proforma_footer_notice:
                 "AVVISO: Questa fattura proforma è solo una stima e non una richiesta di pagamento."
                     .to_string(),
+
+            // Tax summary
+            tax_rate_header: "Aliquota".to_string(),
+            net_amount_header: "Imponibile".to_string(),
+            tax_category_zero_rated: "Aliquota zero".to_string(),
+            tax_category_exempt: "Esente".to_string(),
+            tax_category_reverse_charge: "Inversione contabile".to_string(),
+            tax_category_out_of_scope: "Fuori campo IVA".to_string(),
+
+            // Tax types
+            tax_vat_label: "IVA".to_string(),
+            tax_gst_label: "GST".to_string(),
+            tax_hst_label: "HST".to_string(),
+            tax_pst_label: "PST".to_string(),
+            tax_qst_label: "QST".to_string(),
+            tax_state_label: "Imposta statale".to_string(),
+            tax_county_label: "Imposta di contea".to_string(),
+            tax_city_label: "Imposta comunale".to_string(),
+            tax_included_label: "di cui {tax}:".to_string(),
+
+            // Tax treatment notices
+            reverse_charge_notice: "Inversione contabile (art. 196 Direttiva IVA)".to_string(),
+            intra_community_notice:
+                "Cessione intracomunitaria non imponibile (art. 41 D.L. 331/93)".to_string(),
+            export_notice: "Operazione non imponibile, esportazione (art. 8 DPR 633/72)"
+                .to_string(),
+            exempt_notice: "Operazione esente IVA (art. 10 DPR 633/72)".to_string(),
+            small_business_notice: "IVA non applicata, regime per le piccole imprese".to_string(),
+            small_business_notice_germany: "IVA non addebitata ai sensi del § 19 UStG (regime per piccole imprese)".to_string(),
+            small_business_notice_france: "IVA non applicabile, art. 293 B del CGI".to_string(),
+            small_business_notice_italy: "Operazione in franchigia da IVA, regime forfettario (art. 1, c. 54-89, L. 190/2014)".to_string(),
+            small_business_notice_portugal: "Esente IVA ai sensi dell'art. 53 del CIVA".to_string(),
+
+            // Seller footer
+            directors_label: "Amministratori:".to_string(),
+            share_capital_label: "Capitale sociale:".to_string(),
+            tax_id_label: "Codice fiscale:".to_string(),
+            bank_label: "Banca:".to_string(),
+
+            // Payment information
+            payment_info_label: "Informazioni di pagamento:".to_string(),
+            due_date_label: "Scadenza:".to_string(),
+            account_holder_label: "Intestatario:".to_string(),
+            iban_label: "IBAN:".to_string(),
+            bic_label: "BIC:".to_string(),
+            account_number_label: "N. conto:".to_string(),
+            routing_number_label: "Codice banca:".to_string(),
+
+            // Invoice dates
+            supply_date_label: "Data di consegna:".to_string(),
+            service_period_label: "Periodo di servizio:".to_string(),
+
+            // Customer references
+            customer_number_label: "Codice cliente:".to_string(),
+            customer_reference_label: "N. ordine cliente:".to_string(),
+            contact_person_label: "Referente:".to_string(),
+            cost_center_label: "Centro di costo:".to_string(),
+
+            // Order status
+            status_pending: "In attesa".to_string(),
+            status_paid: "Pagato".to_string(),
+            status_processing: "In lavorazione".to_string(),
+            status_shipped: "Spedito".to_string(),
+            status_completed: "Completato".to_string(),
+            status_cancelled: "Annullato".to_string(),
+            status_refunded: "Rimborsato".to_string(),
+
+            // Watermarks
+            watermark_paid: "PAGATO".to_string(),
+            watermark_draft: "BOZZA".to_string(),
+            watermark_copy: "COPIA".to_string(),
+            watermark_cancelled: "ANNULLATO".to_string(),
+            watermark_void: "NULLO".to_string(),
         }
     }
 
+    /// Looks up a label by its field name, e.g. `"invoice_title"`
+    pub fn label(&self, key: &str) -> Option<&str> {
+        let label = match key {
+            "invoice_title" => &self.invoice_title,
+            "from_label" => &self.from_label,
+            "ship_to_label" => &self.ship_to_label,
+            "bill_to_label" => &self.bill_to_label,
+            "phone_label" => &self.phone_label,
+            "vat_label" => &self.vat_label,
+            "product_header" => &self.product_header,
+            "quantity_header" => &self.quantity_header,
+            "unit_price_header" => &self.unit_price_header,
+            "discount_header" => &self.discount_header,
+            "tax_header" => &self.tax_header,
+            "total_header" => &self.total_header,
+            "subtotal_before_discount_label" => &self.subtotal_before_discount_label,
+            "discount_label" => &self.discount_label,
+            "subtotal_label" => &self.subtotal_label,
+            "shipping_label" => &self.shipping_label,
+            "tax_label" => &self.tax_label,
+            "total_label" => &self.total_label,
+            "notes_label" => &self.notes_label,
+            "invoice_number_prefix" => &self.invoice_number_prefix,
+            "date_label" => &self.date_label,
+            "order_status_label" => &self.order_status_label,
+            "packing_list_title" => &self.packing_list_title,
+            "proforma_invoice_title" => &self.proforma_invoice_title,
+            "sku_header" => &self.sku_header,
+            "packed_header" => &self.packed_header,
+            "return_address_label" => &self.return_address_label,
+            "shipping_method_label" => &self.shipping_method_label,
+            "package_info_title" => &self.package_info_title,
+            "package_weight_label" => &self.package_weight_label,
+            "package_dimensions_label" => &self.package_dimensions_label,
+            "carrier_label" => &self.carrier_label,
+            "tracking_number_label" => &self.tracking_number_label,
+            "total_items_label" => &self.total_items_label,
+            "packer_verification_title" => &self.packer_verification_title,
+            "packed_by_label" => &self.packed_by_label,
+            "signature_label" => &self.signature_label,
+            "authorized_by_label" => &self.authorized_by_label,
+            "estimated_total_label" => &self.estimated_total_label,
+            "proforma_notice" => &self.proforma_notice,
+            "proforma_footer_notice" => &self.proforma_footer_notice,
+            "tax_rate_header" => &self.tax_rate_header,
+            "net_amount_header" => &self.net_amount_header,
+            "tax_category_zero_rated" => &self.tax_category_zero_rated,
+            "tax_category_exempt" => &self.tax_category_exempt,
+            "tax_category_reverse_charge" => &self.tax_category_reverse_charge,
+            "tax_category_out_of_scope" => &self.tax_category_out_of_scope,
+            "tax_vat_label" => &self.tax_vat_label,
+            "tax_gst_label" => &self.tax_gst_label,
+            "tax_hst_label" => &self.tax_hst_label,
+            "tax_pst_label" => &self.tax_pst_label,
+            "tax_qst_label" => &self.tax_qst_label,
+            "tax_state_label" => &self.tax_state_label,
+            "tax_county_label" => &self.tax_county_label,
+            "tax_city_label" => &self.tax_city_label,
+            "tax_included_label" => &self.tax_included_label,
+            "reverse_charge_notice" => &self.reverse_charge_notice,
+            "intra_community_notice" => &self.intra_community_notice,
+            "export_notice" => &self.export_notice,
+            "exempt_notice" => &self.exempt_notice,
+            "small_business_notice" => &self.small_business_notice,
+            "small_business_notice_germany" => &self.small_business_notice_germany,
+            "small_business_notice_france" => &self.small_business_notice_france,
+            "small_business_notice_italy" => &self.small_business_notice_italy,
+            "small_business_notice_portugal" => &self.small_business_notice_portugal,
+            "directors_label" => &self.directors_label,
+            "share_capital_label" => &self.share_capital_label,
+            "tax_id_label" => &self.tax_id_label,
+            "bank_label" => &self.bank_label,
+            "payment_info_label" => &self.payment_info_label,
+            "due_date_label" => &self.due_date_label,
+            "account_holder_label" => &self.account_holder_label,
+            "iban_label" => &self.iban_label,
+            "bic_label" => &self.bic_label,
+            "account_number_label" => &self.account_number_label,
+            "routing_number_label" => &self.routing_number_label,
+            "supply_date_label" => &self.supply_date_label,
+            "service_period_label" => &self.service_period_label,
+            "customer_number_label" => &self.customer_number_label,
+            "customer_reference_label" => &self.customer_reference_label,
+            "contact_person_label" => &self.contact_person_label,
+            "cost_center_label" => &self.cost_center_label,
+            "status_pending" => &self.status_pending,
+            "status_paid" => &self.status_paid,
+            "status_processing" => &self.status_processing,
+            "status_shipped" => &self.status_shipped,
+            "status_completed" => &self.status_completed,
+            "status_cancelled" => &self.status_cancelled,
+            "status_refunded" => &self.status_refunded,
+            "watermark_paid" => &self.watermark_paid,
+            "watermark_draft" => &self.watermark_draft,
+            "watermark_copy" => &self.watermark_copy,
+            "watermark_cancelled" => &self.watermark_cancelled,
+            "watermark_void" => &self.watermark_void,
+            _ => return None,
+        };
+        Some(label)
+    }
+
     pub fn for_language(language: Language) -> Self {
         let base = Dictionary::default();
         match language {
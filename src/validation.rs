@@ -0,0 +1,517 @@
+//! Consistency checks for orders before they are rendered.
+
+use rust_decimal::Decimal;
+use std::fmt;
+
+use crate::templates::errors::PdfError;
+use crate::templates::pdf_utils::format_date;
+use crate::types::{Order, OrderLineItem, SupplyDate, TaxTreatment};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProblemKind {
+    // Text field is empty or whitespace only
+    Empty,
+    // Quantity is zero or negative
+    NotPositive(i64),
+    // Amount differs from the one derived from other fields
+    Mismatch { expected: Decimal, actual: Decimal },
+    // Text does not have the expected format, e.g. a VAT number
+    InvalidFormat(String),
+}
+
+/// A single inconsistency, e.g. `items[1].total` not matching subtotal + tax.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationProblem {
+    // Field path, e.g. "total" or "items[0].quantity"
+    pub field: String,
+    pub kind: ProblemKind,
+}
+
+impl fmt::Display for ProblemKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProblemKind::Empty => write!(f, "must not be empty"),
+            ProblemKind::NotPositive(value) => write!(f, "must be positive, got {}", value),
+            ProblemKind::Mismatch { expected, actual } => {
+                write!(f, "expected {}, got {}", expected, actual)
+            }
+            ProblemKind::InvalidFormat(reason) => write!(f, "is invalid: {}", reason),
+        }
+    }
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.kind)
+    }
+}
+
+impl From<ValidationProblem> for PdfError {
+    fn from(problem: ValidationProblem) -> Self {
+        PdfError::Validation {
+            field: problem.field,
+            reason: problem.kind.to_string(),
+        }
+    }
+}
+
+// Formats of EU VAT numbers after the country prefix:
+// '9' is a digit, 'A' a letter, 'X' a digit or letter, anything else is literal
+const VAT_NUMBER_FORMATS: &[(&str, &[&str])] = &[
+    ("AT", &["U99999999"]),
+    ("BE", &["9999999999"]),
+    ("BG", &["999999999", "9999999999"]),
+    ("CY", &["99999999A"]),
+    ("CZ", &["99999999", "999999999", "9999999999"]),
+    ("DE", &["999999999"]),
+    ("DK", &["99999999"]),
+    ("EE", &["999999999"]),
+    ("EL", &["999999999"]),
+    ("ES", &["X9999999X"]),
+    ("FI", &["99999999"]),
+    ("FR", &["XX999999999"]),
+    ("HR", &["99999999999"]),
+    ("HU", &["99999999"]),
+    ("IE", &["9999999A", "9999999AA", "9X99999A"]),
+    ("IT", &["99999999999"]),
+    ("LT", &["999999999", "999999999999"]),
+    ("LU", &["99999999"]),
+    ("LV", &["99999999999"]),
+    ("MT", &["99999999"]),
+    ("NL", &["999999999B99"]),
+    ("PL", &["9999999999"]),
+    ("PT", &["999999999"]),
+    (
+        "RO",
+        &[
+            "99",
+            "999",
+            "9999",
+            "99999",
+            "999999",
+            "9999999",
+            "99999999",
+            "999999999",
+            "9999999999",
+        ],
+    ),
+    ("SE", &["999999999901"]),
+    ("SI", &["99999999"]),
+    ("SK", &["9999999999"]),
+    ("XI", &["999999999", "999999999999", "GD999", "HA999"]),
+];
+
+fn matches_format(number: &str, format: &str) -> bool {
+    number.len() == format.len()
+        && number.chars().zip(format.chars()).all(|(c, f)| match f {
+            '9' => c.is_ascii_digit(),
+            'A' => c.is_ascii_uppercase(),
+            'X' => c.is_ascii_digit() || c.is_ascii_uppercase(),
+            _ => c == f,
+        })
+}
+
+/// Checks the format of an EU VAT number by its country prefix, e.g. "DE" followed by
+/// nine digits. Spaces, dots and dashes are ignored. This does not check whether the
+/// number is registered (use VIES for that).
+pub fn validate_vat_number(vat_number: &str) -> Result<(), String> {
+    let normalized: String = vat_number
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '.' | '-'))
+        .flat_map(char::to_uppercase)
+        .collect();
+    let prefix = normalized.get(..2).unwrap_or_default();
+    let Some((_, formats)) = VAT_NUMBER_FORMATS.iter().find(|(p, _)| *p == prefix) else {
+        return Err(format!("'{}' has no EU country prefix", vat_number));
+    };
+    let number = &normalized[2..];
+    if formats.iter().any(|format| matches_format(number, format)) {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' does not match {}",
+            vat_number,
+            formats
+                .iter()
+                .map(|format| format!("{}{}", prefix, format))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        ))
+    }
+}
+
+/// Checks the length and the mod-97 check digits of an IBAN. Spaces are ignored.
+pub fn validate_iban(iban: &str) -> Result<(), String> {
+    let normalized: String = iban
+        .chars()
+        .filter(|c| *c != ' ')
+        .flat_map(char::to_uppercase)
+        .collect();
+    if !(15..=34).contains(&normalized.len())
+        || !normalized.chars().all(|c| c.is_ascii_alphanumeric())
+        || !normalized[..2].chars().all(|c| c.is_ascii_uppercase())
+        || !normalized[2..4].chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(format!(
+            "'{}' is not a country code, two check digits and up to 30 letters or digits",
+            iban
+        ));
+    }
+    // Country code and check digits move to the end; letters count as 10 to 35
+    let remainder =
+        normalized[4..]
+            .chars()
+            .chain(normalized[..4].chars())
+            .fold(0, |remainder, c| {
+                let value = c.to_digit(36).unwrap_or_default();
+                let shift = if value < 10 { 10 } else { 100 };
+                (remainder * shift + value) % 97
+            });
+    if remainder == 1 {
+        Ok(())
+    } else {
+        Err(format!("'{}' has wrong check digits", iban))
+    }
+}
+
+// Amounts may differ by rounding, e.g. tax rounded per line vs per document
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+fn check_amount(
+    problems: &mut Vec<ValidationProblem>,
+    field: String,
+    expected: Decimal,
+    actual: Decimal,
+) {
+    if (expected - actual).abs() > TOLERANCE {
+        problems.push(ValidationProblem {
+            field,
+            kind: ProblemKind::Mismatch { expected, actual },
+        });
+    }
+}
+
+impl Order {
+    /// Checks that the order's amounts are consistent with each other and with `items`.
+    /// Amounts may differ by up to 0.01. Returns an empty list if everything adds up.
+    pub fn validate(&self, items: &[OrderLineItem]) -> Vec<ValidationProblem> {
+        let mut problems = vec![];
+
+        if self.currency.trim().is_empty() {
+            problems.push(ValidationProblem {
+                field: "currency".to_string(),
+                kind: ProblemKind::Empty,
+            });
+        }
+
+        for (i, item) in items.iter().enumerate() {
+            if item.quantity <= 0 {
+                problems.push(ValidationProblem {
+                    field: format!("items[{}].quantity", i),
+                    kind: ProblemKind::NotPositive(item.quantity),
+                });
+            }
+            check_amount(
+                &mut problems,
+                format!("items[{}].subtotal_before_discount", i),
+                item.unit_price * Decimal::from(item.quantity),
+                item.subtotal_before_discount,
+            );
+            check_amount(
+                &mut problems,
+                format!("items[{}].subtotal", i),
+                item.subtotal_before_discount - item.discount_total,
+                item.subtotal,
+            );
+            check_amount(
+                &mut problems,
+                format!("items[{}].total", i),
+                item.subtotal + item.tax_total,
+                item.total,
+            );
+            if !item.tax_lines.is_empty() {
+                check_amount(
+                    &mut problems,
+                    format!("items[{}].tax_total", i),
+                    item.tax_lines.iter().map(|tax| tax.amount).sum(),
+                    item.tax_total,
+                );
+            }
+            // Compound taxes are charged on the subtotal plus the taxes before them
+            let mut taxed = item.subtotal;
+            for (j, tax) in item.tax_lines.iter().enumerate() {
+                let base = if tax.compound { taxed } else { item.subtotal };
+                if let Some(rate) = tax.rate {
+                    check_amount(
+                        &mut problems,
+                        format!("items[{}].tax_lines[{}].amount", i, j),
+                        base * rate / Decimal::ONE_HUNDRED,
+                        tax.amount,
+                    );
+                }
+                taxed += tax.amount;
+            }
+            if let Some(rate) = item.tax_rate {
+                check_amount(
+                    &mut problems,
+                    format!("items[{}].tax_total", i),
+                    item.subtotal * rate / Decimal::ONE_HUNDRED,
+                    item.tax_total,
+                );
+            }
+        }
+
+        check_amount(
+            &mut problems,
+            "subtotal_before_discount".to_string(),
+            items.iter().map(|item| item.subtotal_before_discount).sum(),
+            self.subtotal_before_discount,
+        );
+        check_amount(
+            &mut problems,
+            "discount_total".to_string(),
+            items.iter().map(|item| item.discount_total).sum(),
+            self.discount_total,
+        );
+        check_amount(
+            &mut problems,
+            "subtotal".to_string(),
+            items.iter().map(|item| item.subtotal).sum(),
+            self.subtotal,
+        );
+        if !self.tax_lines.is_empty() {
+            check_amount(
+                &mut problems,
+                "tax_total".to_string(),
+                self.tax_lines.iter().map(|tax| tax.amount).sum(),
+                self.tax_total,
+            );
+        }
+        // Shipping without a rate may be taxed at any rate, so its tax is unknown
+        let shipping_tax = match self.shipping_tax_rate {
+            Some(rate) => Some(self.shipping_total * rate / Decimal::ONE_HUNDRED),
+            None if self.shipping_total.is_zero() => Some(Decimal::ZERO),
+            None => None,
+        };
+        if let Some(shipping_tax) = shipping_tax {
+            check_amount(
+                &mut problems,
+                "tax_total".to_string(),
+                items.iter().map(|item| item.tax_total).sum::<Decimal>() + shipping_tax,
+                self.tax_total,
+            );
+        }
+        check_amount(
+            &mut problems,
+            "total".to_string(),
+            self.subtotal + self.shipping_total + self.tax_total,
+            self.total,
+        );
+
+        let treatment = self.tax_treatment;
+        if matches!(
+            treatment,
+            TaxTreatment::ReverseCharge | TaxTreatment::IntraCommunity
+        ) {
+            // The buyer's VAT number is required on the invoice
+            match self.billing_address.vat_number.as_deref() {
+                None | Some("") => problems.push(ValidationProblem {
+                    field: "billing_address.vat_number".to_string(),
+                    kind: ProblemKind::Empty,
+                }),
+                Some(vat_number) => {
+                    if let Err(reason) = validate_vat_number(vat_number) {
+                        problems.push(ValidationProblem {
+                            field: "billing_address.vat_number".to_string(),
+                            kind: ProblemKind::InvalidFormat(reason),
+                        });
+                    }
+                }
+            }
+        }
+        if treatment != TaxTreatment::Standard {
+            check_amount(
+                &mut problems,
+                "tax_total".to_string(),
+                Decimal::ZERO,
+                self.tax_total,
+            );
+        }
+
+        if let Some(invoice) = &self.invoice {
+            if invoice.number.trim().is_empty() {
+                problems.push(ValidationProblem {
+                    field: "invoice.number".to_string(),
+                    kind: ProblemKind::Empty,
+                });
+            }
+            if let Some(SupplyDate::Period { start, end }) = invoice.supply_date {
+                if end < start {
+                    problems.push(ValidationProblem {
+                        field: "invoice.supply_date".to_string(),
+                        kind: ProblemKind::InvalidFormat(format!(
+                            "period ends {} before it starts {}",
+                            format_date(end),
+                            format_date(start)
+                        )),
+                    });
+                }
+            }
+        }
+
+        if let Some(payment) = &self.payment {
+            if let Some(due_date) = payment.due_date {
+                let invoice_date = self.invoice_date();
+                if due_date < invoice_date {
+                    problems.push(ValidationProblem {
+                        field: "payment.due_date".to_string(),
+                        kind: ProblemKind::InvalidFormat(format!(
+                            "{} is before the invoice date {}",
+                            format_date(due_date),
+                            format_date(invoice_date)
+                        )),
+                    });
+                }
+            }
+            for (i, account) in payment.bank_accounts.iter().enumerate() {
+                if let Some(Err(reason)) = account.iban.as_deref().map(validate_iban) {
+                    problems.push(ValidationProblem {
+                        field: format!("payment.bank_accounts[{}].iban", i),
+                        kind: ProblemKind::InvalidFormat(reason),
+                    });
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::{Calculator, RoundingLevel, RoundingMode};
+    use crate::test_utils::{line, order};
+
+    fn fields(problems: &[ValidationProblem]) -> Vec<&str> {
+        problems
+            .iter()
+            .map(|problem| problem.field.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn accepts_calculated_orders() {
+        let lines: Vec<_> = (0..10)
+            .map(|i| line(&i.to_string(), 1, Decimal::new(7, 2), Decimal::from(19)))
+            .chain([line("b", 3, Decimal::new(333, 2), Decimal::from(7))])
+            .collect();
+        for rounding_level in [RoundingLevel::PerLine, RoundingLevel::PerDocument] {
+            for rounding_mode in [RoundingMode::HalfUp, RoundingMode::HalfEven] {
+                let calculator = Calculator {
+                    rounding_mode,
+                    rounding_level,
+                    decimal_places: 2,
+                };
+                let (order, items) =
+                    order(&calculator, &lines, Decimal::new(499, 2), Decimal::from(19));
+                assert_eq!(order.validate(&items), vec![]);
+            }
+        }
+    }
+
+    #[test]
+    fn line_taxes_must_add_up_to_tax_total() {
+        let lines = [line("1", 1, Decimal::from(100), Decimal::from(19))];
+        let (mut order, items) =
+            order(&Calculator::default(), &lines, Decimal::ZERO, Decimal::ZERO);
+        order.tax_total += Decimal::ONE;
+        order.total += Decimal::ONE;
+        assert_eq!(
+            order.validate(&items),
+            vec![ValidationProblem {
+                field: "tax_total".to_string(),
+                kind: ProblemKind::Mismatch {
+                    expected: Decimal::from(19),
+                    actual: Decimal::from(20),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn shipping_tax_counts_towards_tax_total() {
+        let lines = [line("1", 1, Decimal::from(100), Decimal::from(19))];
+        let (mut order, items) = order(
+            &Calculator::default(),
+            &lines,
+            Decimal::from(10),
+            Decimal::from(19),
+        );
+        assert_eq!(order.tax_total, Decimal::new(2090, 2));
+        assert_eq!(order.validate(&items), vec![]);
+
+        // Without a rate the shipping tax is unknown
+        order.shipping_tax_rate = None;
+        assert_eq!(order.validate(&items), vec![]);
+
+        order.shipping_tax_rate = Some(Decimal::from(7));
+        assert_eq!(fields(&order.validate(&items)), vec!["tax_total"]);
+    }
+
+    #[test]
+    fn reports_line_problems() {
+        let lines = [line("1", 2, Decimal::from(5), Decimal::from(10))];
+        let (order, mut items) =
+            order(&Calculator::default(), &lines, Decimal::ZERO, Decimal::ZERO);
+        items[0].quantity = 0;
+        items[0].total = Decimal::ONE;
+        assert_eq!(
+            fields(&order.validate(&items)),
+            vec![
+                "items[0].quantity",
+                "items[0].subtotal_before_discount",
+                "items[0].total"
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_iban_digits() {
+        assert_eq!(validate_iban("DE89 3704 0044 0532 0130 00"), Ok(()));
+        assert_eq!(validate_iban("gb82west12345698765432"), Ok(()));
+        assert_eq!(
+            validate_iban("DE88 3704 0044 0532 0130 00"),
+            Err("'DE88 3704 0044 0532 0130 00' has wrong check digits".to_string())
+        );
+        assert!(validate_iban("DE89").is_err());
+        assert!(validate_iban("1289 3704 0044 0532 0130 00").is_err());
+        assert!(validate_iban("DE89 3704 0044 0532 0130 0!").is_err());
+    }
+
+    #[test]
+    fn checks_vat_number_formats() {
+        for valid in [
+            "DE123456789",
+            "de 123.456.789",
+            "ATU12345678",
+            "FRAB123456789",
+            "NL123456789B01",
+            "IE1234567AB",
+            "RO12",
+        ] {
+            assert_eq!(validate_vat_number(valid), Ok(()), "{}", valid);
+        }
+        assert_eq!(
+            validate_vat_number("DE12345678"),
+            Err("'DE12345678' does not match DE999999999".to_string())
+        );
+        assert_eq!(
+            validate_vat_number("US123456789"),
+            Err("'US123456789' has no EU country prefix".to_string())
+        );
+        assert!(validate_vat_number("AT12345678").is_err());
+        assert!(validate_vat_number("").is_err());
+        assert!(validate_vat_number("Ä").is_err());
+    }
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{address, image_count, properties, sample_order, LOGO};
+use tradedoc::templates::batch::{generate_pdf_batch, BatchEntry, BatchOptions, DocumentKind};
+use tradedoc::templates::invoice::generate_pdf_invoice;
+use tradedoc::types::{Dictionary, Language};
+
+#[test]
+fn embeds_the_logo_once() {
+    let (order, items) = sample_order(3);
+    let warehouse = address("Lager");
+    let (single, _) = generate_pdf_invoice(
+        &order,
+        &items,
+        &warehouse,
+        properties(),
+        Dictionary::for_language(Language::English),
+        Some(LOGO),
+    )
+    .unwrap();
+
+    let entries: Vec<BatchEntry> = [
+        DocumentKind::Invoice,
+        DocumentKind::ProformaInvoice,
+        DocumentKind::PackingList,
+        DocumentKind::Invoice,
+    ]
+    .into_iter()
+    .map(|kind| BatchEntry {
+        order: &order,
+        order_items: &items,
+        kind,
+    })
+    .collect();
+    let (batch, report) = generate_pdf_batch(
+        &entries,
+        &warehouse,
+        properties(),
+        Dictionary::for_language(Language::English),
+        Some(LOGO),
+        &BatchOptions::default(),
+    )
+    .unwrap();
+
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+    assert!(image_count(&single) > 0);
+    assert_eq!(image_count(&batch), image_count(&single));
+}
@@ -0,0 +1,212 @@
+//! Orders and properties shared by the render tests.
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use printpdf::{Op, PdfDocument, PdfPage};
+use rust_decimal::Decimal;
+use tradedoc::calculator::{Calculator, LineInput};
+use tradedoc::templates::document::{render_pages, DocumentTemplate, TemplateContext};
+use tradedoc::templates::errors::PdfError;
+use tradedoc::templates::pdf_utils::load_fonts;
+use tradedoc::types::{
+    Address, BankAccount, Dictionary, DocumentProperties, InvoiceMeta, Language, Order,
+    OrderLineItem, OrderStatus, PaymentInfo, PricingMode, SellerInfo, SupplyDate, TaxTreatment,
+};
+
+pub const LOGO: &[u8] = include_bytes!("../../assets/gofranz.png");
+
+pub fn address(name: &str) -> Address {
+    Address {
+        recipient_name: Some(name.to_string()),
+        company_name: Some("Müller & Söhne GmbH".to_string()),
+        street: "Königstraße 12".to_string(),
+        street2: None,
+        city: "Frankfurt am Main".to_string(),
+        state: "Hesse".to_string(),
+        country: "Germany".to_string(),
+        zip: "60311".to_string(),
+        phone: Some("+49 69 123 456".to_string()),
+        vat_number: Some("DE123456789".to_string()),
+    }
+}
+
+pub fn line(i: usize, title: &str) -> LineInput {
+    LineInput {
+        id: format!("ITEM{}", i),
+        title: title.to_string(),
+        sku: Some(format!("SKU-{:03}", i)),
+        quantity: 1 + (i as i64 % 3),
+        unit_price: Decimal::new(1999 + 100 * i as i64, 2),
+        unit_discount: Decimal::ZERO,
+        tax_rate: if i % 4 == 3 {
+            Decimal::from(7)
+        } else {
+            Decimal::from(19)
+        },
+        tax_category: None,
+    }
+}
+
+/// An order of `lines` whose amounts are derived by the default [`Calculator`]
+pub fn order(lines: &[LineInput]) -> (Order, Vec<OrderLineItem>) {
+    let created_at = NaiveDate::from_ymd_opt(2024, 3, 1)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    let mut order = Order {
+        id: "ORD-2024-001".to_string(),
+        shipping_address: address("Jörg Weiß"),
+        billing_address: address("Anaïs Çelik"),
+        customer_number: Some("C-1001".to_string()),
+        customer_reference: Some("PO-4711".to_string()),
+        contact_person: None,
+        cost_center: None,
+        custom_fields: vec![],
+        currency: "€".to_string(),
+        status: OrderStatus::Completed,
+        shipping_method: "Express".to_string(),
+        pricing_mode: PricingMode::Net,
+        tax_treatment: TaxTreatment::Standard,
+        shipping_total: Decimal::ZERO,
+        shipping_tax_rate: None,
+        shipping_tax_category: None,
+        subtotal_before_discount: Decimal::ZERO,
+        discount_total: Decimal::ZERO,
+        subtotal: Decimal::ZERO,
+        tax_total: Decimal::ZERO,
+        tax_lines: vec![],
+        total: Decimal::ZERO,
+        notes: Some("Vielen Dank für Ihren Einkauf!".to_string()),
+        payment: Some(PaymentInfo {
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 15),
+            terms: Some("Zahlbar innerhalb von 14 Tagen.".to_string()),
+            bank_accounts: vec![BankAccount {
+                bank_name: "Commerzbank".to_string(),
+                iban: Some("DE89 3704 0044 0532 0130 00".to_string()),
+                bic: Some("COBADEFFXXX".to_string()),
+                ..BankAccount::default()
+            }],
+        }),
+        invoice: Some(InvoiceMeta {
+            number: "2024-0042".to_string(),
+            issue_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            supply_date: NaiveDate::from_ymd_opt(2024, 2, 28).map(SupplyDate::Date),
+        }),
+        created_at,
+        updated_at: created_at,
+    };
+    let calculation =
+        Calculator::default().calculate(lines, Decimal::new(495, 2), Decimal::from(19));
+    calculation.totals.apply_to(&mut order);
+    (order, calculation.items)
+}
+
+/// An order of `count` lines with short German titles
+pub fn sample_order(count: usize) -> (Order, Vec<OrderLineItem>) {
+    let lines: Vec<LineInput> = (0..count)
+        .map(|i| line(i, &format!("Bürostuhl {}", i + 1)))
+        .collect();
+    order(&lines)
+}
+
+pub fn properties() -> DocumentProperties {
+    DocumentProperties {
+        font_normal_path: None,
+        font_bold_path: None,
+        background_color: None,
+        font_size_title: None,
+        font_size_body: None,
+        font_size_label: None,
+        watermark: None,
+        signature_image: None,
+        seal_image: None,
+        strict_glyphs: None,
+        validate: Some(true),
+        small_business_exemption: None,
+        seller: None,
+    }
+}
+
+/// Number of images embedded in `pdf`, counting soft masks
+pub fn image_count(pdf: &[u8]) -> usize {
+    pdf.windows(b"/Subtype/Image".len())
+        .filter(|window| *window == b"/Subtype/Image")
+        .count()
+}
+
+pub fn seller() -> SellerInfo {
+    SellerInfo {
+        legal_name: "Müller & Söhne GmbH".to_string(),
+        registration_court: Some("Amtsgericht Frankfurt am Main".to_string()),
+        registration_ids: vec![("HRB".to_string(), "12345".to_string())],
+        tax_id: Some("045 123 45678".to_string()),
+        vat_number: Some("DE123456789".to_string()),
+        directors: vec!["Jörg Weiß".to_string()],
+        share_capital: Some("EUR 25.000,00".to_string()),
+        bank_accounts: vec![BankAccount {
+            bank_name: "Commerzbank".to_string(),
+            iban: Some("DE89 3704 0044 0532 0130 00".to_string()),
+            bic: Some("COBADEFFXXX".to_string()),
+            ..BankAccount::default()
+        }],
+        website: Some("example.com".to_string()),
+        email: Some("info@example.com".to_string()),
+        phone: Some("+49 69 123 456".to_string()),
+    }
+}
+
+/// Pages of `template` for `order`, with the logo
+pub fn render(
+    template: &dyn DocumentTemplate,
+    order: &Order,
+    items: &[OrderLineItem],
+    properties: DocumentProperties,
+    language: Language,
+) -> Result<Vec<PdfPage>, PdfError> {
+    let properties = properties.input_or_default();
+    let translation = Dictionary::for_language(language);
+    let warehouse = address("Lager");
+    let mut doc = PdfDocument::new("Test");
+    let fonts = load_fonts(&mut doc, Some(language), None, None)?;
+    let ctx = TemplateContext {
+        order,
+        order_items: items,
+        warehouse_address: &warehouse,
+        properties: &properties,
+        translation: &translation,
+        fonts: &fonts,
+        logo_data: Some(LOGO),
+    };
+    render_pages(&mut doc, template, &ctx)
+}
+
+/// Text written by `ops`, one entry per text section
+pub fn texts(ops: &[Op]) -> Vec<String> {
+    let mut texts = vec![];
+    for op in ops {
+        match op {
+            Op::StartTextSection => texts.push(String::new()),
+            Op::WriteText { items, .. } => {
+                for item in items {
+                    if let printpdf::TextItem::Text(text) = item {
+                        if let Some(last) = texts.last_mut() {
+                            last.push_str(text);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    texts
+}
+
+/// y positions (in points) text is written at
+pub fn text_positions(ops: &[Op]) -> Vec<f32> {
+    ops.iter()
+        .filter_map(|op| match op {
+            Op::SetTextCursor { pos } => Some(pos.y.0),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{address, properties, sample_order, seller, LOGO};
+use printpdf::{Op, PdfDocument};
+use tradedoc::templates::declarative::DeclarativeTemplate;
+use tradedoc::templates::document::{render_pages, DocumentTemplate, TemplateContext};
+use tradedoc::templates::invoice::Invoice;
+use tradedoc::templates::packing_list::PackingList;
+use tradedoc::templates::pdf_utils::load_fonts;
+use tradedoc::templates::proforma_invoice::ProformaInvoice;
+use tradedoc::types::{Dictionary, DocumentProperties, Language, Order, OrderLineItem};
+
+/// Operations of every page of `code` and `json`, rendered with the same fonts and images
+fn render_both(
+    code: &dyn DocumentTemplate,
+    json: &DeclarativeTemplate,
+    order: &Order,
+    items: &[OrderLineItem],
+    properties: DocumentProperties,
+    language: Language,
+) -> (Vec<Vec<Op>>, Vec<Vec<Op>>) {
+    let properties = properties.input_or_default();
+    let translation = Dictionary::for_language(language);
+    let warehouse = address("Lager");
+    let mut doc = PdfDocument::new("Test");
+    let fonts = load_fonts(&mut doc, Some(language), None, None).unwrap();
+    let ctx = TemplateContext {
+        order,
+        order_items: items,
+        warehouse_address: &warehouse,
+        properties: &properties,
+        translation: &translation,
+        fonts: &fonts,
+        logo_data: Some(LOGO),
+    };
+    let ops = |pages: Vec<printpdf::PdfPage>| pages.into_iter().map(|page| page.ops).collect();
+    (
+        ops(render_pages(&mut doc, code, &ctx).unwrap()),
+        ops(render_pages(&mut doc, json, &ctx).unwrap()),
+    )
+}
+
+fn assert_same_output(order: &Order, items: &[OrderLineItem], properties: DocumentProperties) {
+    let templates: [(&dyn DocumentTemplate, DeclarativeTemplate); 3] = [
+        (&Invoice, DeclarativeTemplate::invoice().unwrap()),
+        (
+            &ProformaInvoice,
+            DeclarativeTemplate::proforma_invoice().unwrap(),
+        ),
+        (&PackingList, DeclarativeTemplate::packing_list().unwrap()),
+    ];
+    for language in [Language::English, Language::German, Language::Thai] {
+        for (code, json) in &templates {
+            let (code_ops, json_ops) =
+                render_both(*code, json, order, items, properties.clone(), language);
+            assert_eq!(
+                code_ops.len(),
+                json_ops.len(),
+                "pages of {} in {:?}",
+                code.title(),
+                language
+            );
+            for (page, (code_page, json_page)) in code_ops.iter().zip(&json_ops).enumerate() {
+                // Point at the first difference instead of printing whole pages
+                let same = code_page
+                    .iter()
+                    .zip(json_page)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                assert!(
+                    same == code_page.len() && same == json_page.len(),
+                    "{} in {:?}, page {}, op {}: {:?} != {:?}",
+                    code.title(),
+                    language,
+                    page + 1,
+                    same,
+                    code_page.get(same..same + 3),
+                    json_page.get(same..same + 3),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn json_templates_match_code_templates() {
+    let (order, items) = sample_order(4);
+    assert_same_output(&order, &items, properties());
+}
+
+#[test]
+fn json_templates_match_code_templates_with_signature_and_seal() {
+    let (order, items) = sample_order(4);
+    assert_same_output(
+        &order,
+        &items,
+        DocumentProperties {
+            signature_image: Some(LOGO.to_vec()),
+            seal_image: Some(LOGO.to_vec()),
+            ..properties()
+        },
+    );
+    assert_same_output(
+        &order,
+        &items,
+        DocumentProperties {
+            seal_image: Some(LOGO.to_vec()),
+            ..properties()
+        },
+    );
+}
+
+// Watermarks get a new graphics state id per render, see tests/pagination.rs for them
+#[test]
+fn json_templates_break_pages_like_code_templates() {
+    for count in [20, 32, 60] {
+        let (order, items) = sample_order(count);
+        assert_same_output(
+            &order,
+            &items,
+            DocumentProperties {
+                seller: Some(seller()),
+                signature_image: Some(LOGO.to_vec()),
+                ..properties()
+            },
+        );
+    }
+}
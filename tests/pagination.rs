@@ -0,0 +1,126 @@
+mod common;
+
+use common::{properties, render, sample_order, seller, text_positions, texts};
+use printpdf::{Op, PdfPage};
+use tradedoc::templates::declarative::DeclarativeTemplate;
+use tradedoc::templates::document::{content_bottom, DocumentTemplate, CONTINUATION_TOP};
+use tradedoc::templates::invoice::Invoice;
+use tradedoc::templates::packing_list::PackingList;
+use tradedoc::templates::proforma_invoice::ProformaInvoice;
+use tradedoc::types::{Dictionary, DocumentProperties, Language, Watermark, WatermarkContent};
+
+fn templates() -> Vec<Box<dyn DocumentTemplate>> {
+    vec![
+        Box::new(Invoice),
+        Box::new(ProformaInvoice),
+        Box::new(PackingList),
+        Box::new(DeclarativeTemplate::invoice().unwrap()),
+        Box::new(DeclarativeTemplate::proforma_invoice().unwrap()),
+        Box::new(DeclarativeTemplate::packing_list().unwrap()),
+    ]
+}
+
+fn paged_properties() -> DocumentProperties {
+    DocumentProperties {
+        seller: Some(seller()),
+        watermark: Some(Watermark::new(WatermarkContent::Paid)),
+        signature_image: Some(common::LOGO.to_vec()),
+        ..properties()
+    }
+}
+
+/// Operations every page ends with: the seller footer and the watermark
+fn shared_tail(pages: &[PdfPage]) -> usize {
+    let last = &pages[pages.len() - 1].ops;
+    (0..last.len())
+        .take_while(|&n| {
+            pages.iter().all(|page| {
+                page.ops.len() > n && page.ops[page.ops.len() - 1 - n] == last[last.len() - 1 - n]
+            })
+        })
+        .count()
+}
+
+#[test]
+fn long_orders_continue_on_new_pages() {
+    let (order, items) = sample_order(60);
+    let bottom = content_bottom(
+        &paged_properties().input_or_default(),
+        &Dictionary::for_language(Language::German),
+    ) as f32;
+    for template in templates() {
+        let pages = render(
+            template.as_ref(),
+            &order,
+            &items,
+            paged_properties(),
+            Language::German,
+        )
+        .unwrap();
+        assert!(pages.len() >= 2, "{}", template.title());
+
+        // Footer and watermark on every page
+        let tail = shared_tail(&pages);
+        let footer = texts(&pages[0].ops[pages[0].ops.len() - tail..]);
+        assert!(footer.iter().any(|text| text.contains("Amtsgericht")));
+        assert!(footer.iter().any(|text| text == "BEZAHLT"), "{:?}", footer);
+
+        // All pages share the watermark's graphics state
+        let mut states = pages
+            .iter()
+            .flat_map(|page| &page.ops)
+            .filter_map(|op| match op {
+                Op::LoadGraphicsState { gs } => Some(gs.0.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        states.dedup();
+        assert_eq!(states.len(), 1, "{}", template.title());
+
+        let mut rows = 0;
+        for (i, page) in pages.iter().enumerate() {
+            let body = &page.ops[..page.ops.len() - tail];
+            for y in text_positions(body) {
+                assert!(
+                    y >= bottom - 0.1,
+                    "{} page {}: {}",
+                    template.title(),
+                    i + 1,
+                    y
+                );
+            }
+            if i > 0 {
+                assert!(text_positions(body)
+                    .iter()
+                    .all(|y| *y <= CONTINUATION_TOP as f32 + 15.0));
+            }
+            let texts = texts(body);
+            // The header row is repeated on every page with rows
+            let page_rows = texts
+                .iter()
+                .filter(|text| text.starts_with("Bürostuhl"))
+                .count();
+            if page_rows > 0 {
+                assert!(texts.iter().any(|text| text == "Produkt"), "page {}", i + 1);
+            }
+            rows += page_rows;
+        }
+        assert_eq!(rows, 60, "{}", template.title());
+    }
+}
+
+#[test]
+fn short_orders_fit_on_one_page() {
+    let (order, items) = sample_order(3);
+    for template in templates() {
+        let pages = render(
+            template.as_ref(),
+            &order,
+            &items,
+            properties(),
+            Language::English,
+        )
+        .unwrap();
+        assert_eq!(pages.len(), 1, "{}", template.title());
+    }
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use common::{address, line, order, properties, LOGO};
+use tradedoc::templates::declarative::DeclarativeTemplate;
+use tradedoc::templates::diagnostics::{DiagnosticKind, GenerationReport, Severity};
+use tradedoc::templates::document::{generate_pdf, DocumentTemplate};
+use tradedoc::templates::errors::PdfError;
+use tradedoc::templates::invoice::Invoice;
+use tradedoc::templates::packing_list::PackingList;
+use tradedoc::templates::proforma_invoice::ProformaInvoice;
+use tradedoc::types::{Dictionary, DocumentProperties, Language, Order, OrderLineItem};
+
+const TITLES: &[&str] = &[
+    "เก้าอี้สำนักงานปรับระดับได้พร้อมพนักพิงศีรษะและที่วางแขน",
+    "Höhenverstellbarer Schreibtisch mit Kabelwanne, Eiche geölt, 160 × 80 cm",
+    "Ergonomische Fußstütze",
+    "กระดาษ A4 80 แกรม",
+];
+
+fn templates() -> Vec<Box<dyn DocumentTemplate>> {
+    vec![
+        Box::new(Invoice),
+        Box::new(ProformaInvoice),
+        Box::new(PackingList),
+        Box::new(DeclarativeTemplate::invoice().unwrap()),
+        Box::new(DeclarativeTemplate::proforma_invoice().unwrap()),
+        Box::new(DeclarativeTemplate::packing_list().unwrap()),
+    ]
+}
+
+/// Thai and German text longer than the columns it is printed in
+fn long_order() -> (Order, Vec<OrderLineItem>) {
+    let lines: Vec<_> = (0..12).map(|i| line(i, TITLES[i % TITLES.len()])).collect();
+    let (mut order, items) = order(&lines);
+    order.shipping_address = address("บริษัท ตัวอย่าง จำกัด (มหาชน) สำนักงานใหญ่ กรุงเทพมหานคร");
+    order.notes = Some("กรุณาส่งสินค้าในช่วงเวลาทำการ ".repeat(12));
+    order.shipping_method = "Spedition mit Hebebühne und Terminvereinbarung".to_string();
+    (order, items)
+}
+
+/// Renders `template` and checks nothing worse than truncated text or, outside Thai
+/// documents, missing Thai glyphs was reported
+fn render(
+    template: &dyn DocumentTemplate,
+    order: &Order,
+    items: &[OrderLineItem],
+    properties: DocumentProperties,
+    language: Language,
+) -> GenerationReport {
+    let (pdf, report) = generate_pdf(
+        template,
+        order,
+        items,
+        &address("Lager Süd"),
+        properties,
+        Dictionary::for_language(language),
+        Some(LOGO),
+    )
+    .unwrap_or_else(|e| panic!("{} in {:?}: {}", template.title(), language, e));
+    assert!(pdf.starts_with(b"%PDF"));
+    for diagnostic in report.warnings() {
+        // Only Thai documents load a font with Thai glyphs
+        let expected = match diagnostic.kind {
+            DiagnosticKind::TextTruncated => true,
+            DiagnosticKind::MissingGlyph => language != Language::Thai,
+            _ => false,
+        };
+        assert!(
+            expected && diagnostic.severity == Severity::Warning,
+            "{} in {:?}: {:?}",
+            template.title(),
+            language,
+            diagnostic
+        );
+    }
+    report
+}
+
+#[test]
+fn renders_long_non_ascii_text() {
+    let (order, items) = long_order();
+    for template in templates() {
+        for language in [Language::English, Language::German, Language::Thai] {
+            let report = render(template.as_ref(), &order, &items, properties(), language);
+            // The long titles don't fit the product column
+            assert!(
+                report
+                    .warnings()
+                    .any(|diagnostic| diagnostic.message.contains("เก้าอี้")),
+                "{} in {:?}",
+                template.title(),
+                language
+            );
+        }
+    }
+}
+
+#[test]
+fn renders_custom_fields_in_large_type() {
+    let (mut order, items) = long_order();
+    order.custom_fields = (1..=6)
+        .map(|i| {
+            (
+                format!("Projektnummer {}:", i),
+                format!("ÄÖÜ-{:04}-โครงการ", i),
+            )
+        })
+        .collect();
+    for template in templates() {
+        for font_size in [7.0, 14.0, 70.0] {
+            render(
+                template.as_ref(),
+                &order,
+                &items,
+                DocumentProperties {
+                    font_size_body: Some(font_size),
+                    font_size_label: Some(font_size),
+                    font_size_title: Some(font_size),
+                    ..properties()
+                },
+                Language::Thai,
+            );
+        }
+    }
+}
+
+#[test]
+fn reports_missing_glyphs_wherever_text_is_drawn() {
+    let (mut order, items) = long_order();
+    if let Some(payment) = &mut order.payment {
+        payment.bank_accounts[0].account_number = Some("口座 1234567".to_string());
+    }
+    let mut translation = Dictionary::for_language(Language::German);
+    translation.invoice_title = "請求書".to_string();
+    let literal = DeclarativeTemplate::from_json(
+        r#"{ "title": "Test", "body": [{ "type": "text", "text": "納品書 {order.id}" }] }"#,
+    )
+    .unwrap();
+
+    let generate = |template: &dyn DocumentTemplate, strict| {
+        generate_pdf(
+            template,
+            &order,
+            &items,
+            &address("Lager Süd"),
+            DocumentProperties {
+                strict_glyphs: Some(strict),
+                ..properties()
+            },
+            translation.clone(),
+            None,
+        )
+    };
+    for (template, text) in [
+        (&Invoice as &dyn DocumentTemplate, "請求書"),
+        (&Invoice, "口座"),
+        (&literal, "納品書 ORD-2024-001"),
+    ] {
+        let (_, report) = generate(template, false).unwrap();
+        assert!(
+            report
+                .warnings()
+                .any(|diagnostic| diagnostic.kind == DiagnosticKind::MissingGlyph
+                    && diagnostic.message.contains(text)),
+            "{}",
+            text
+        );
+        assert!(matches!(
+            generate(template, true),
+            Err(PdfError::MissingGlyph(_))
+        ));
+    }
+}